@@ -0,0 +1,513 @@
+use crate::config::devnet;
+use crate::config::project::ProjectConfig;
+use crate::error::{Result, ZeckitError};
+use crate::output;
+use crate::utils::{check_docker, check_docker_compose, format_bytes};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+/// Free disk below which image builds are likely to fail.
+const DISK_MINIMUM: u64 = 10 * GB;
+/// Free disk for building every image and keeping chain data.
+const DISK_RECOMMENDED: u64 = 30 * GB;
+
+/// Memory available to Docker below which Rust image builds get killed.
+const MEMORY_MINIMUM: u64 = 4 * GB;
+const MEMORY_RECOMMENDED: u64 = 8 * GB;
+
+/// Services with a fixed `container_name` in `docker-compose.yml`.
+const DEVNET_CONTAINERS: [&str; 5] = ["zebra", "lightwalletd", "zaino", "zingo-wallet", "faucet"];
+
+/// Sapling proving parameters, as fetched by `zcash-fetch-params`.
+const SAPLING_PARAMS: [&str; 2] = ["sapling-spend.params", "sapling-output.params"];
+
+/// `zeckit doctor --output json`.
+#[derive(Debug, Serialize)]
+struct DoctorReport {
+    ok: bool,
+    checks: Vec<Check>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+    /// Needs a working Docker daemon.
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: String,
+    status: Status,
+    detail: String,
+    /// What to do about a warning or failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fix: Option<String>,
+}
+
+impl Check {
+    fn new(name: &str, status: Status, detail: impl Into<String>) -> Self {
+        Self { name: name.to_string(), status, detail: detail.into(), fix: None }
+    }
+
+    fn ok(name: &str, detail: impl Into<String>) -> Self {
+        Self::new(name, Status::Ok, detail)
+    }
+
+    fn warn(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { fix: Some(fix.into()), ..Self::new(name, Status::Warn, detail) }
+    }
+
+    fn fail(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { fix: Some(fix.into()), ..Self::new(name, Status::Fail, detail) }
+    }
+
+    fn skipped(name: &str) -> Self {
+        Self::new(name, Status::Skipped, "Docker daemon not reachable")
+    }
+}
+
+/// The parts of `docker info` the checks use.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerInfo {
+    server_version: String,
+    mem_total: u64,
+    docker_root_dir: PathBuf,
+}
+
+/// One container as listed by `docker ps --format json`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerContainer {
+    names: String,
+    state: String,
+    /// `key=value` pairs separated by commas.
+    #[serde(default)]
+    labels: String,
+}
+
+impl DockerContainer {
+    fn compose_project(&self) -> Option<&str> {
+        self.labels
+            .split(',')
+            .find_map(|label| label.strip_prefix("com.docker.compose.project="))
+    }
+}
+
+pub async fn execute(name: Option<String>) -> Result<()> {
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!("{}", "  ZecKit - Doctor".cyan().bold());
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!();
+
+    // Outside a project, check against the defaults `zeckit init` would use
+    let config = match devnet::load_project(name.as_deref()) {
+        Ok(config) => config,
+        Err(_) if name.is_none() => {
+            say!("  No zeckit.toml found; checking the default ports and names");
+            say!();
+            ProjectConfig::load(&std::env::current_dir()?)?
+        }
+        Err(e) => return Err(e),
+    };
+
+    let mut checks = Vec::new();
+    let info = check_daemon(&mut checks);
+    checks.push(check_compose());
+
+    match &info {
+        Some(info) => {
+            checks.push(check_disk(info, &config.root));
+            checks.push(check_memory(info));
+        }
+        None => {
+            checks.push(Check::skipped("Disk space"));
+            checks.push(Check::skipped("Memory"));
+        }
+    }
+
+    // Ports held by this devnet's own containers are expected to be taken
+    let devnet_running = match &info {
+        Some(_) => list_containers()
+            .map(|containers| {
+                let project = compose_project_name(&config);
+                containers
+                    .iter()
+                    .any(|c| c.compose_project() == Some(project.as_str()) && c.state == "running")
+            })
+            .unwrap_or(false),
+        None => false,
+    };
+    checks.extend(check_ports(&config, devnet_running));
+
+    checks.push(check_params(&params_dir()));
+
+    match &info {
+        Some(_) => checks.push(check_containers(&config)),
+        None => checks.push(Check::skipped("Containers")),
+    }
+
+    for check in &checks {
+        print_check(check);
+    }
+    say!();
+
+    let failures = checks.iter().filter(|check| check.status == Status::Fail).count();
+    let warnings = checks.iter().filter(|check| check.status == Status::Warn).count();
+    output::emit(&DoctorReport { ok: failures == 0, checks })?;
+
+    if failures > 0 {
+        return Err(ZeckitError::HealthCheck(format!(
+            "{} problem(s) found, {} warning(s)",
+            failures, warnings
+        )));
+    }
+
+    if warnings > 0 {
+        say!("{}", format!("✓ No problems found ({} warning(s))", warnings).yellow().bold());
+    } else {
+        say!("{}", "✓ No problems found".green().bold());
+    }
+    say!();
+
+    Ok(())
+}
+
+/// Docker installed and the daemon reachable. Returns `docker info` when it is.
+fn check_daemon(checks: &mut Vec<Check>) -> Option<DockerInfo> {
+    if !check_docker() {
+        checks.push(Check::fail(
+            "Docker",
+            "docker not found",
+            "Install Docker: https://docs.docker.com/get-docker/",
+        ));
+        return None;
+    }
+
+    match docker(&["info", "--format", "{{json .}}"]) {
+        Ok(stdout) => match serde_json::from_str::<DockerInfo>(&stdout) {
+            Ok(info) => {
+                checks.push(Check::ok("Docker", format!("daemon {}", info.server_version)));
+                Some(info)
+            }
+            Err(e) => {
+                checks.push(Check::fail(
+                    "Docker",
+                    format!("unexpected `docker info` output: {}", e),
+                    "Check that `docker info` works",
+                ));
+                None
+            }
+        },
+        Err(stderr) => {
+            let fix = if stderr.contains("permission denied") {
+                "Add your user to the docker group (`sudo usermod -aG docker $USER`) and log in again"
+            } else {
+                "Start the Docker daemon (`sudo systemctl start docker`, or open Docker Desktop)"
+            };
+            checks.push(Check::fail("Docker", stderr, fix));
+            None
+        }
+    }
+}
+
+fn check_compose() -> Check {
+    if !check_docker_compose() {
+        return Check::fail(
+            "Docker Compose",
+            "`docker compose` not available",
+            "Install the Compose v2 plugin: https://docs.docker.com/compose/install/",
+        );
+    }
+
+    match docker(&["compose", "version", "--short"]) {
+        Ok(version) => {
+            let version = version.trim().trim_start_matches('v').to_string();
+            let major = version.split('.').next().and_then(|major| major.parse::<u32>().ok());
+            match major {
+                Some(major) if major >= 2 => Check::ok("Docker Compose", format!("v{}", version)),
+                _ => Check::fail(
+                    "Docker Compose",
+                    format!("version {} (v2 required)", version),
+                    "Install the Compose v2 plugin: https://docs.docker.com/compose/install/",
+                ),
+            }
+        }
+        Err(stderr) => Check::fail("Docker Compose", stderr, "Check that `docker compose version` works"),
+    }
+}
+
+/// Free space where Docker keeps images and volumes. With Docker Desktop that
+/// directory lives in a VM, so the project's disk is checked instead.
+fn check_disk(info: &DockerInfo, project_root: &Path) -> Check {
+    let (path, available) = match available_space(&info.docker_root_dir) {
+        Some(available) => (info.docker_root_dir.as_path(), available),
+        None => match available_space(project_root) {
+            Some(available) => (project_root, available),
+            None => {
+                return Check::warn("Disk space", "could not determine free space", "Check `df -h` by hand");
+            }
+        },
+    };
+
+    let detail = format!("{} free on {}", format_bytes(available), path.display());
+    let fix = format!(
+        "Free up space (e.g. `docker system prune`); building every image needs about {}",
+        format_bytes(DISK_RECOMMENDED)
+    );
+    if available < DISK_MINIMUM {
+        Check::fail("Disk space", detail, fix)
+    } else if available < DISK_RECOMMENDED {
+        Check::warn("Disk space", detail, fix)
+    } else {
+        Check::ok("Disk space", detail)
+    }
+}
+
+/// Bytes available on the filesystem holding `path`, from `df`.
+fn available_space(path: &Path) -> Option<u64> {
+    let output = Command::new("df").arg("-Pk").arg(path).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let kilobytes: u64 = stdout.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+fn check_memory(info: &DockerInfo) -> Check {
+    let detail = format!("{} available to Docker", format_bytes(info.mem_total));
+    let fix = format!(
+        "Give Docker at least {} (Docker Desktop: Settings → Resources)",
+        format_bytes(MEMORY_RECOMMENDED)
+    );
+    if info.mem_total < MEMORY_MINIMUM {
+        Check::fail("Memory", detail, fix)
+    } else if info.mem_total < MEMORY_RECOMMENDED {
+        Check::warn("Memory", detail, fix)
+    } else {
+        Check::ok("Memory", detail)
+    }
+}
+
+/// The host ports from `[ports]`, each of which must be free unless this
+/// devnet is running.
+fn check_ports(config: &ProjectConfig, devnet_running: bool) -> Vec<Check> {
+    let ports = [
+        ("zebra_rpc", "Zebra RPC", config.ports.zebra_rpc),
+        ("zebra_p2p", "Zebra P2P", config.ports.zebra_p2p),
+        ("indexer_grpc", "Indexer gRPC", config.ports.indexer_grpc),
+        ("faucet", "Faucet", config.ports.faucet),
+    ];
+
+    ports
+        .into_iter()
+        .map(|(key, label, port)| {
+            let name = format!("Port {} ({})", port, label);
+            if TcpListener::bind(("0.0.0.0", port)).is_ok() {
+                Check::ok(&name, "free")
+            } else if devnet_running {
+                Check::ok(&name, "in use by the running devnet")
+            } else {
+                Check::fail(
+                    &name,
+                    "in use by another process",
+                    format!(
+                        "Stop whatever listens on {} (`lsof -i :{}`), or set `ports.{}` in zeckit.toml",
+                        port, port, key
+                    ),
+                )
+            }
+        })
+        .collect()
+}
+
+/// Where zcashd and `zcash-fetch-params` keep the proving parameters.
+fn params_dir() -> PathBuf {
+    let home = std::env::var_os("HOME").map(PathBuf::from).unwrap_or_default();
+    if cfg!(target_os = "macos") {
+        home.join("Library/Application Support/ZcashParams")
+    } else {
+        home.join(".zcash-params")
+    }
+}
+
+/// The devnet's containers bring their own parameters; host-side provers
+/// without bundled parameters need them here.
+fn check_params(dir: &Path) -> Check {
+    let missing: Vec<&str> = SAPLING_PARAMS
+        .into_iter()
+        .filter(|file| !dir.join(file).is_file())
+        .collect();
+
+    if missing.is_empty() {
+        Check::ok("Zcash parameters", dir.display().to_string())
+    } else {
+        Check::warn(
+            "Zcash parameters",
+            format!("{} missing from {}", missing.join(", "), dir.display()),
+            "Run `zcash-fetch-params` if host-side wallets or provers need them",
+        )
+    }
+}
+
+/// Containers that would get in the way of `zeckit up`: this devnet's
+/// stopped containers, and containers of another compose project holding the
+/// devnet's container names.
+fn check_containers(config: &ProjectConfig) -> Check {
+    let containers = match list_containers() {
+        Ok(containers) => containers,
+        Err(stderr) => return Check::warn("Containers", stderr, "Check that `docker ps -a` works"),
+    };
+
+    let names: Vec<String> = DEVNET_CONTAINERS
+        .into_iter()
+        .chain(config.extra_services())
+        .map(|service| config.container(service))
+        .collect();
+    let (stopped, conflicts) = stale_containers(&containers, &names, &compose_project_name(config));
+
+    if !conflicts.is_empty() {
+        return Check::fail(
+            "Containers",
+            format!("owned by another compose project: {}", conflicts.join(", ")),
+            format!("Remove them with `docker rm -f {}`", conflicts.join(" ")),
+        );
+    }
+    if !stopped.is_empty() {
+        let down = match &config.devnet_name {
+            Some(name) => format!("zeckit down --name {}", name),
+            None => "zeckit down".to_string(),
+        };
+        return Check::warn(
+            "Containers",
+            format!("stopped from a previous run: {}", stopped.join(", ")),
+            format!("Remove them with `{}`, or start over with `zeckit up --fresh`", down),
+        );
+    }
+
+    Check::ok("Containers", "no stale containers")
+}
+
+/// Of the containers named in `names`: those of `project` that aren't
+/// running, and those belonging to any other project.
+fn stale_containers(containers: &[DockerContainer], names: &[String], project: &str) -> (Vec<String>, Vec<String>) {
+    let mut stopped = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for container in containers.iter().filter(|c| names.contains(&c.names)) {
+        if container.compose_project() != Some(project) {
+            conflicts.push(container.names.clone());
+        } else if container.state != "running" {
+            stopped.push(container.names.clone());
+        }
+    }
+
+    (stopped, conflicts)
+}
+
+/// Compose project of the selected devnet: its own name, or compose's
+/// default derived from the project directory.
+fn compose_project_name(config: &ProjectConfig) -> String {
+    config.compose_project().unwrap_or_else(|| {
+        config
+            .root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '-' || *c == '_')
+            .collect()
+    })
+}
+
+fn list_containers() -> std::result::Result<Vec<DockerContainer>, String> {
+    let stdout = docker(&["ps", "--all", "--format", "{{json .}}"])?;
+    stdout
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// Run `docker args`, returning stdout, or stderr when it fails.
+fn docker(args: &[&str]) -> std::result::Result<String, String> {
+    let output = Command::new("docker").args(args).output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+fn print_check(check: &Check) {
+    let (mark, line) = match check.status {
+        Status::Ok => ("✓".green(), format!("{:<28} {}", check.name, check.detail).normal()),
+        Status::Warn => ("!".yellow(), format!("{:<28} {}", check.name, check.detail).yellow()),
+        Status::Fail => ("✗".red(), format!("{:<28} {}", check.name, check.detail).red()),
+        Status::Skipped => ("-".dimmed(), format!("{:<28} skipped ({})", check.name, check.detail).dimmed()),
+    };
+    say!("  {} {}", mark, line);
+
+    if let Some(fix) = &check.fix {
+        say!("      → {}", fix);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(name: &str, state: &str, project: &str) -> DockerContainer {
+        DockerContainer {
+            names: name.to_string(),
+            state: state.to_string(),
+            labels: format!("com.docker.compose.service=x,com.docker.compose.project={},other=1", project),
+        }
+    }
+
+    #[test]
+    fn test_stale_containers() {
+        let containers = [
+            container("zeckit-zebra", "running", "myapp"),
+            container("zeckit-faucet", "exited", "myapp"),
+            container("zeckit-zaino", "running", "old-checkout"),
+            container("unrelated", "exited", "other"),
+        ];
+        let names: Vec<String> = ["zeckit-zebra", "zeckit-faucet", "zeckit-zaino"].map(String::from).to_vec();
+
+        let (stopped, conflicts) = stale_containers(&containers, &names, "myapp");
+        assert_eq!(stopped, ["zeckit-faucet"]);
+        assert_eq!(conflicts, ["zeckit-zaino"]);
+    }
+
+    #[test]
+    fn test_compose_project_name() {
+        let mut config = ProjectConfig::load(Path::new("/work/My App.v2")).unwrap();
+        assert_eq!(compose_project_name(&config), "myappv2");
+
+        config.devnet_name = Some("ci".into());
+        assert_eq!(compose_project_name(&config), "zeckit-ci");
+    }
+
+    #[test]
+    fn test_check_params() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(check_params(dir.path()).status, Status::Warn);
+
+        for file in SAPLING_PARAMS {
+            std::fs::write(dir.path().join(file), b"").unwrap();
+        }
+        assert_eq!(check_params(dir.path()).status, Status::Ok);
+    }
+}
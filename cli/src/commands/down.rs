@@ -1,25 +1,31 @@
+use crate::config::devnet::{self, DevnetState};
 use crate::docker::compose::DockerCompose;
 use crate::error::Result;
 use colored::*;
 
-pub async fn execute(purge: bool) -> Result<()> {
-    println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-    println!("{}", "  ZecKit - Stopping Devnet".cyan().bold());
-    println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-    println!();
+pub async fn execute(purge: bool, name: Option<String>) -> Result<()> {
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!("{}", "  ZecKit - Stopping Devnet".cyan().bold());
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!();
     
-    let compose = DockerCompose::new()?;
+    let config = devnet::load_project(name.as_deref())?;
+    let compose = DockerCompose::new(&config)?;
     
-    println!("{} Stopping services...", "🛑".yellow());
+    say!("{} Stopping services...", "🛑".yellow());
     compose.down(purge)?;
     
+    if let Some(name) = &name {
+        DevnetState::remove(&config.root, name)?;
+    }
+    
     if purge {
-        println!("{} Volumes removed (fresh start on next up)", "✓".green());
+        say!("{} Volumes removed (fresh start on next up)", "✓".green());
     }
     
-    println!();
-    println!("{}", "✓ Devnet stopped successfully".green().bold());
-    println!();
+    say!();
+    say!("{}", "✓ Devnet stopped successfully".green().bold());
+    say!();
     
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,182 @@
+use crate::config::project::{CONFIG_FILE, GENERATED_DIR};
+use crate::error::{Result, ZeckitError};
+use colored::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Build context for the faucet image in scaffolded projects, which don't
+/// contain the faucet sources.
+const FAUCET_BUILD_CONTEXT: &str = "https://github.com/Supercoolkayy/ZecKit.git#main";
+
+/// Files written by `zeckit init`, relative to the project root.
+const SCAFFOLD: &[(&str, &str)] = &[
+    (CONFIG_FILE, include_str!("../../../zeckit.toml")),
+    ("docker-compose.yml", include_str!("../../../docker-compose.yml")),
+    ("docker/configs/zebra.toml", include_str!("../../../docker/configs/zebra.toml")),
+    ("docker/zebra/Dockerfile", include_str!("../../../docker/zebra/Dockerfile")),
+    ("docker/zebra/entrypoint.sh", include_str!("../../../docker/zebra/entrypoint.sh")),
+    ("docker/lightwalletd/Dockerfile", include_str!("../../../docker/lightwalletd/Dockerfile")),
+    ("docker/lightwalletd/entrypoint.sh", include_str!("../../../docker/lightwalletd/entrypoint.sh")),
+    ("docker/zaino/Dockerfile", include_str!("../../../docker/zaino/Dockerfile")),
+    ("docker/zaino/entrypoint.sh", include_str!("../../../docker/zaino/entrypoint.sh")),
+    ("docker/zingo/Dockerfile", include_str!("../../../docker/zingo/Dockerfile")),
+    ("docker/zingo/entrypoint.sh", include_str!("../../../docker/zingo/entrypoint.sh")),
+    ("fixtures/.gitkeep", ""),
+];
+
+pub async fn execute(dir: Option<PathBuf>, force: bool) -> Result<()> {
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!("{}", "  ZecKit - Initializing Project".cyan().bold());
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!();
+
+    let root = match dir {
+        Some(dir) => dir,
+        None => std::env::current_dir()?,
+    };
+
+    let written = write_scaffold(&root, force)?;
+    for path in &written {
+        say!("  {} {}", "✓".green(), path.display());
+    }
+
+    if ignore_generated_dir(&root)? {
+        say!("  {} .gitignore ({})", "✓".green(), GENERATED_DIR);
+    }
+
+    say!();
+    say!("{}", format!("✓ ZecKit project initialized in {}", root.display()).green().bold());
+    say!();
+    say!("Next steps:");
+    say!("  • Edit {} to choose a backend, ports and extra services", CONFIG_FILE);
+    say!("  • Start the devnet: zeckit up");
+    say!();
+
+    Ok(())
+}
+
+/// Write the scaffold into `root`, returning the paths written. Refuses to
+/// overwrite existing files unless `force` is set.
+fn write_scaffold(root: &Path, force: bool) -> Result<Vec<PathBuf>> {
+    if !force {
+        let existing: Vec<&str> = SCAFFOLD
+            .iter()
+            .map(|(path, _)| *path)
+            .filter(|path| root.join(path).exists())
+            .collect();
+
+        if !existing.is_empty() {
+            return Err(ZeckitError::Config(format!(
+                "Refusing to overwrite existing files (use --force): {}",
+                existing.join(", ")
+            )));
+        }
+    }
+
+    let mut written = Vec::new();
+    for (relative, contents) in SCAFFOLD {
+        let path = root.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let contents = if *relative == "docker-compose.yml" {
+            scaffold_compose(contents)
+        } else {
+            contents.to_string()
+        };
+        fs::write(&path, contents)?;
+
+        if relative.ends_with(".sh") {
+            make_executable(&path)?;
+        }
+
+        written.push(PathBuf::from(relative));
+    }
+
+    Ok(written)
+}
+
+/// The bundled compose file builds the faucet from this repository's
+/// sources; scaffolded projects build it from the upstream repository.
+fn scaffold_compose(compose: &str) -> String {
+    compose.replace(
+        "context: .\n      dockerfile: zeckit-faucet/Dockerfile",
+        &format!("context: {}\n      dockerfile: zeckit-faucet/Dockerfile", FAUCET_BUILD_CONTEXT),
+    )
+}
+
+/// Add the generated directory to `.gitignore`. Returns whether it changed.
+fn ignore_generated_dir(root: &Path) -> Result<bool> {
+    let path = root.join(".gitignore");
+    let entry = format!("{}/", GENERATED_DIR);
+
+    let mut contents = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+
+    if contents.lines().any(|line| line.trim() == entry || line.trim() == GENERATED_DIR) {
+        return Ok(false);
+    }
+
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(&format!("\n# Files rendered from {}\n{}\n", CONFIG_FILE, entry));
+    fs::write(&path, contents)?;
+
+    Ok(true)
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::project::ProjectConfig;
+
+    #[test]
+    fn test_scaffold_is_a_loadable_project() {
+        let dir = tempfile::tempdir().unwrap();
+
+        write_scaffold(dir.path(), false).unwrap();
+        assert!(ignore_generated_dir(dir.path()).unwrap());
+        assert!(!ignore_generated_dir(dir.path()).unwrap());
+
+        let config = ProjectConfig::load(dir.path()).unwrap();
+        assert!(config.zebra_template_path().exists());
+        config.render_zebra_config(Some("tmScaffoldTest")).unwrap();
+        assert!(config.zebra_config_path().starts_with(dir.path().join(GENERATED_DIR)));
+        assert!(dir.path().join("fixtures").is_dir());
+
+        let compose = fs::read_to_string(dir.path().join("docker-compose.yml")).unwrap();
+        assert!(compose.contains(FAUCET_BUILD_CONTEXT));
+        assert!(!compose.contains("context: .\n"));
+    }
+
+    #[test]
+    fn test_existing_files_are_not_overwritten() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("docker-compose.yml"), "services: {}\n").unwrap();
+
+        assert!(matches!(write_scaffold(dir.path(), false), Err(ZeckitError::Config(_))));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("docker-compose.yml")).unwrap(),
+            "services: {}\n"
+        );
+
+        write_scaffold(dir.path(), true).unwrap();
+    }
+}
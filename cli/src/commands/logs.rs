@@ -0,0 +1,284 @@
+use crate::config::devnet;
+use crate::config::project::{ProjectConfig, CONFIG_FILE};
+use crate::docker::compose::{ContainerState, DockerCompose};
+use crate::error::{Result, ZeckitError};
+use colored::*;
+use regex::Regex;
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+/// Prefix colors, assigned to services in order.
+const COLORS: [Color; 6] = [
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Blue,
+    Color::BrightRed,
+];
+
+/// Options of `zeckit logs`.
+pub struct LogsOptions {
+    /// Services to show, by name or prefix (`faucet` matches `faucet-lwd`).
+    /// All of the devnet's services when empty.
+    pub services: Vec<String>,
+    pub follow: bool,
+    /// Passed to `docker compose logs --since`, e.g. `10m` or a timestamp.
+    pub since: Option<String>,
+    /// Only show lines matching this regex.
+    pub grep: Option<String>,
+    /// Write a bug-report tarball here instead of printing.
+    pub bundle: Option<PathBuf>,
+}
+
+pub async fn execute(options: LogsOptions, name: Option<String>) -> Result<()> {
+    let config = devnet::load_project(name.as_deref())?;
+    let compose = DockerCompose::new(&config)?;
+    let containers = compose.containers()?;
+    let services = select_services(&containers, &options.services)?;
+
+    if let Some(path) = &options.bundle {
+        bundle(&config, &compose, &containers, &services, options.since.as_deref(), path, name.as_deref())?;
+        say!("Wrote {}", path.display());
+        return Ok(());
+    }
+
+    if services.is_empty() {
+        return Err(ZeckitError::Config("No containers found; start the devnet with `zeckit up`".into()));
+    }
+
+    let grep = options
+        .grep
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .map_err(|e| ZeckitError::Config(format!("Invalid --grep pattern: {}", e)))?;
+
+    let (sender, lines) = mpsc::channel();
+    let mut children = Vec::new();
+    for (i, service) in services.iter().enumerate() {
+        let mut child = compose.spawn_logs(service, options.follow, options.since.as_deref())?;
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(i, stdout, sender.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(i, stderr, sender.clone());
+        }
+        children.push(child);
+    }
+    drop(sender);
+
+    let width = services.iter().map(String::len).max().unwrap_or_default();
+    let print = |(i, line): (usize, String)| {
+        let (_, text) = split_timestamp(&line);
+        if grep.as_ref().is_none_or(|grep| grep.is_match(text)) {
+            let prefix = format!("{:<width$} |", services[i], width = width);
+            println!("{} {}", prefix.color(COLORS[i % COLORS.len()]), text);
+        }
+    };
+
+    if options.follow {
+        lines.into_iter().for_each(print);
+    } else {
+        // Each service's logs arrive separately; interleave them by time
+        let mut all: Vec<(usize, String)> = lines.into_iter().collect();
+        all.sort_by(|(_, a), (_, b)| split_timestamp(a).0.cmp(split_timestamp(b).0));
+        all.into_iter().for_each(print);
+    }
+
+    wait_all(&services, children)
+}
+
+/// Services of `containers` matching `requested`, by name or `name-`
+/// prefix. All of them when nothing is requested.
+fn select_services(containers: &[ContainerState], requested: &[String]) -> Result<Vec<String>> {
+    let mut known: Vec<String> = Vec::new();
+    for container in containers {
+        if !known.contains(&container.service) {
+            known.push(container.service.clone());
+        }
+    }
+
+    if requested.is_empty() {
+        return Ok(known);
+    }
+
+    let mut selected = Vec::new();
+    for name in requested {
+        let matches: Vec<&String> = known
+            .iter()
+            .filter(|service| *service == name || service.starts_with(&format!("{}-", name)))
+            .collect();
+
+        if matches.is_empty() {
+            return Err(ZeckitError::Config(format!(
+                "No container for service '{}' (available: {})",
+                name,
+                known.join(", ")
+            )));
+        }
+        for service in matches {
+            if !selected.contains(service) {
+                selected.push(service.clone());
+            }
+        }
+    }
+
+    Ok(selected)
+}
+
+/// Split the RFC 3339 timestamp `--timestamps` puts in front of each line.
+/// Lines without one (e.g. compose's own errors) get an empty timestamp.
+fn split_timestamp(line: &str) -> (&str, &str) {
+    match line.split_once(' ') {
+        Some((timestamp, text)) if timestamp.len() >= 20 && timestamp.as_bytes()[10] == b'T' => (timestamp, text),
+        _ => ("", line),
+    }
+}
+
+/// Send each line read from `reader`, tagged with `service`, to `sender`.
+fn forward_lines(service: usize, reader: impl Read + Send + 'static, sender: Sender<(usize, String)>) {
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(|line| line.ok()) {
+            if sender.send((service, line)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+fn wait_all(services: &[String], children: Vec<Child>) -> Result<()> {
+    for (service, mut child) in services.iter().zip(children) {
+        if !child.wait()?.success() {
+            return Err(ZeckitError::Docker(format!("docker compose logs {} failed", service)));
+        }
+    }
+    Ok(())
+}
+
+/// Write a tarball with everything needed to look into a broken devnet:
+/// service logs, the container list, the project's configs, saved build
+/// logs and `zeckit status --output json`. Also works when the devnet never
+/// came up, for a failed build.
+fn bundle(
+    config: &ProjectConfig,
+    compose: &DockerCompose,
+    containers: &[ContainerState],
+    services: &[String],
+    since: Option<&str>,
+    path: &Path,
+    name: Option<&str>,
+) -> Result<()> {
+    let staging = tempfile::tempdir()?;
+    let root = staging.path().join("zeckit-bundle");
+    fs::create_dir_all(root.join("logs"))?;
+    fs::create_dir_all(root.join("config"))?;
+
+    for service in services {
+        let output = compose.spawn_logs(service, false, since)?.wait_with_output()?;
+        let mut log = output.stdout;
+        log.extend(output.stderr);
+        fs::write(root.join("logs").join(format!("{}.log", service)), log)?;
+    }
+
+    fs::write(root.join("ps.json"), serde_json::to_string_pretty(containers)?)?;
+
+    let configs = [
+        config.root.join(CONFIG_FILE),
+        config.root.join("docker-compose.yml"),
+        config.zebra_config_path(),
+        config.services_compose_path(),
+    ];
+    for file in configs.iter().filter(|file| file.exists()) {
+        if let Some(file_name) = file.file_name() {
+            fs::copy(file, root.join("config").join(file_name))?;
+        }
+    }
+
+    // Saved by `zeckit up` when an image build fails
+    let build_logs = config.generated_dir().join("logs");
+    if build_logs.is_dir() {
+        for entry in fs::read_dir(&build_logs)? {
+            let entry = entry?;
+            fs::copy(entry.path(), root.join("logs").join(entry.file_name()))?;
+        }
+    }
+
+    write_status(&root, name)?;
+
+    let path = std::path::absolute(path)?;
+    let output = Command::new("tar")
+        .arg("-czf")
+        .arg(&path)
+        .arg("-C")
+        .arg(staging.path())
+        .arg("zeckit-bundle")
+        .output()?;
+
+    if !output.status.success() {
+        return Err(ZeckitError::Docker(format!(
+            "tar failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Run `zeckit status --output json` and keep its output, including why it
+/// failed if it did.
+fn write_status(root: &Path, name: Option<&str>) -> Result<()> {
+    let mut cmd = Command::new(std::env::current_exe()?);
+    cmd.args(["--output", "json", "status"]);
+    if let Some(name) = name {
+        cmd.args(["--name", name]);
+    }
+
+    let output = cmd.output()?;
+    fs::write(root.join("status.json"), &output.stdout)?;
+    if !output.status.success() {
+        fs::write(root.join("status.stderr.txt"), &output.stderr)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(service: &str) -> ContainerState {
+        ContainerState {
+            service: service.to_string(),
+            name: format!("zeckit-{}", service),
+            state: "running".to_string(),
+            status: "Up 1 minute".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_select_services() {
+        let containers = [container("zebra"), container("lightwalletd"), container("faucet-lwd")];
+
+        assert_eq!(select_services(&containers, &[]).unwrap(), ["zebra", "lightwalletd", "faucet-lwd"]);
+        assert_eq!(
+            select_services(&containers, &["faucet".into(), "zebra".into(), "faucet-lwd".into()]).unwrap(),
+            ["faucet-lwd", "zebra"]
+        );
+        assert!(select_services(&containers, &["zaino".into()]).is_err());
+        assert!(select_services(&[], &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_split_timestamp() {
+        assert_eq!(
+            split_timestamp("2026-10-18T09:12:01.123456789Z INFO zebrad: started"),
+            ("2026-10-18T09:12:01.123456789Z", "INFO zebrad: started")
+        );
+        assert_eq!(split_timestamp("no such service: zaino"), ("", "no such service: zaino"));
+    }
+}
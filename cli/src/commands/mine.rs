@@ -0,0 +1,135 @@
+use crate::config::devnet;
+use crate::config::settings::Settings;
+use crate::docker::compose::DockerCompose;
+use crate::docker::health::HealthChecker;
+use crate::error::{Result, ZeckitError};
+use crate::output;
+use colored::*;
+use indicatif::ProgressBar;
+use tokio::time::{sleep, Duration, Instant};
+use zeckit_rpc::{RpcError, ZebraRpcClient};
+
+/// JSON-RPC "method not found", returned by Zebra builds without `generate`.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+pub async fn execute(
+    count: Option<u32>,
+    until_height: Option<u64>,
+    address: Option<String>,
+    name: Option<String>,
+) -> Result<()> {
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!("{}", "  ZecKit - Mining Blocks".cyan().bold());
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!();
+
+    let config = devnet::load_project(name.as_deref())?;
+    let settings = Settings::from_config(&config);
+    let timeout = Duration::from_secs(config.timeouts.mining);
+
+    // `generate` returns only after the blocks are mined, so allow it as
+    // long as the mining timeout
+    let zebra = ZebraRpcClient::new(&settings.zebra_rpc_url).with_timeout(timeout);
+
+    if let Some(address) = &address {
+        // Zebra only mines to the configured miner address
+        say!("Setting coinbase address to {}...", address);
+        config.render_zebra_config(Some(address))?;
+        DockerCompose::new(&config)?.restart("zebra")?;
+
+        let pb = output::progress(ProgressBar::new_spinner());
+        HealthChecker::new(&settings)
+            .wait_for_zebra(&pb, Duration::from_secs(config.timeouts.zebra))
+            .await?;
+        pb.finish_and_clear();
+    }
+
+    let start_height = zebra.get_block_count().await?;
+    let blocks = match (count, until_height) {
+        (_, Some(target)) => target.saturating_sub(start_height),
+        (Some(count), None) => u64::from(count),
+        (None, None) => 1,
+    };
+
+    if blocks == 0 {
+        say!("Already at height {}, nothing to mine", start_height);
+        return Ok(());
+    }
+
+    let target = start_height + blocks;
+    say!("Mining {} block(s): {} → {}", blocks, start_height, target);
+
+    let blocks = u32::try_from(blocks)
+        .map_err(|_| ZeckitError::Config(format!("Cannot mine {} blocks at once", blocks)))?;
+
+    let hashes = mine_blocks(&zebra, start_height, blocks, timeout).await?;
+
+    say!();
+    for hash in &hashes {
+        say!("  {}", hash);
+    }
+    say!();
+    say!(
+        "{}",
+        format!("✓ Mined {} block(s), height {}", hashes.len(), zebra.get_block_count().await?)
+            .green()
+            .bold()
+    );
+
+    Ok(())
+}
+
+/// Mine `blocks` blocks on top of `start_height` and return their hashes.
+/// Falls back to waiting for Zebra's internal miner when `generate` is not
+/// available.
+pub async fn mine_blocks(
+    zebra: &ZebraRpcClient,
+    start_height: u64,
+    blocks: u32,
+    timeout: Duration,
+) -> Result<Vec<String>> {
+    match zebra.generate(blocks).await {
+        Ok(hashes) => Ok(hashes),
+        Err(RpcError::JsonRpc { code: METHOD_NOT_FOUND, .. }) => {
+            say!("{}", "generate RPC unavailable, waiting for the internal miner...".yellow());
+            let target = start_height + u64::from(blocks);
+            wait_for_height(zebra, target, timeout).await?;
+            block_hashes(zebra, start_height + 1, target).await
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn wait_for_height(zebra: &ZebraRpcClient, target: u64, timeout: Duration) -> Result<()> {
+    let start = Instant::now();
+
+    loop {
+        let height = zebra.get_block_count().await?;
+        say_inline!("\r  Block {} / {}", height, target);
+
+        if height >= target {
+            say!();
+            return Ok(());
+        }
+
+        if start.elapsed() > timeout {
+            say!();
+            return Err(ZeckitError::ServiceNotReady(format!(
+                "Internal miner did not reach height {} within {}s",
+                target,
+                timeout.as_secs()
+            )));
+        }
+
+        sleep(Duration::from_secs(1)).await;
+    }
+}
+
+/// Hashes of the blocks from `from` to `to`, inclusive.
+async fn block_hashes(zebra: &ZebraRpcClient, from: u64, to: u64) -> Result<Vec<String>> {
+    let mut hashes = Vec::new();
+    for height in from..=to {
+        hashes.push(zebra.get_block(&height.to_string()).await?.hash);
+    }
+    Ok(hashes)
+}
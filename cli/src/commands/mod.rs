@@ -1,4 +1,9 @@
+pub mod init;
 pub mod up;
 pub mod down;
 pub mod status;
-pub mod test;
\ No newline at end of file
+pub mod mine;
+pub mod test;
+pub mod logs;
+pub mod doctor;
+pub mod snapshot;
@@ -0,0 +1,584 @@
+use crate::commands::up;
+use crate::config::backend::Backend;
+use crate::config::devnet::{self, DevnetState};
+use crate::config::project::{ProjectConfig, GENERATED_DIR};
+use crate::config::settings::Settings;
+use crate::config::zebra::ZebraConfig;
+use crate::docker::compose::{ComposeProject, ContainerState, DockerCompose};
+use crate::docker::health::HealthChecker;
+use crate::error::{Result, ZeckitError};
+use crate::output;
+use crate::utils::format_bytes;
+use colored::*;
+use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::future::Future;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::{sleep, Duration, Instant};
+use zeckit_rpc::ZebraRpcClient;
+
+/// Version of the snapshot layout. Bump when `Manifest` or the archive
+/// layout changes incompatibly.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Image used to read and write volume contents.
+const HELPER_IMAGE: &str = "alpine:3.20";
+
+/// `manifest.json` at the root of a snapshot archive, next to `volumes/`
+/// (one `<volume>.tar.gz` each) and `config/` (generated configs).
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    name: String,
+    /// Unix time the snapshot was taken.
+    created_at: u64,
+    zeckit_version: String,
+    backend: Backend,
+    heights: Heights,
+    best_block_hash: String,
+    addresses: Addresses,
+    /// Compose volume names, e.g. `zebra-data`.
+    volumes: Vec<String>,
+    /// Image ID of each service's container.
+    images: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Heights {
+    zebra: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    indexer: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    faucet_wallet: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Addresses {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    miner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    faucet: Option<String>,
+}
+
+/// Stop the devnet, archive its chain, indexer, wallet and faucet volumes
+/// with the generated configs, and start it again.
+pub async fn save(snapshot: &str, name: Option<String>) -> Result<()> {
+    validate_name(snapshot)?;
+
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!("{}", "  ZecKit - Saving Snapshot".cyan().bold());
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!();
+
+    let config = devnet::load_project(name.as_deref())?;
+    let settings = Settings::from_config(&config);
+    let compose = DockerCompose::new(&config)?;
+
+    let zebra = ZebraRpcClient::new(&settings.zebra_rpc_url);
+    let chain = zebra.get_blockchain_info().await.map_err(|e| {
+        ZeckitError::ServiceNotReady(format!(
+            "Zebra is not answering ({}); start the devnet with `zeckit up` before saving a snapshot",
+            e
+        ))
+    })?;
+
+    let containers = compose.containers()?;
+    let backend = devnet_backend(&containers);
+
+    let checker = HealthChecker::new(&settings);
+    let (indexer_height, faucet) = if backend.has_wallet() {
+        (
+            checker.indexer_info().await.ok().map(|info| info.block_height),
+            checker.faucet_stats().await.ok(),
+        )
+    } else {
+        (None, None)
+    };
+
+    let project = compose.project()?;
+    let volumes: Vec<String> = backend_volumes(backend)
+        .into_iter()
+        .filter(|volume| project.volumes.contains_key(*volume))
+        .map(str::to_string)
+        .collect();
+
+    let manifest = Manifest {
+        version: SNAPSHOT_VERSION,
+        name: snapshot.to_string(),
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default(),
+        zeckit_version: env!("CARGO_PKG_VERSION").to_string(),
+        backend,
+        heights: Heights {
+            zebra: u64::from(chain.blocks),
+            indexer: indexer_height,
+            faucet_wallet: faucet.as_ref().and_then(|stats| stats.wallet_height),
+        },
+        best_block_hash: chain.bestblockhash,
+        addresses: Addresses {
+            miner: ZebraConfig::load(&config.zebra_config_path())
+                .ok()
+                .and_then(|zebra| zebra.mining.miner_address),
+            faucet: faucet.and_then(|stats| stats.faucet_address),
+        },
+        volumes,
+        images: image_ids(&containers)?,
+    };
+
+    let path = snapshot_path(&config.root, snapshot);
+    let running = compose.running_services()?;
+
+    say!("Stopping services...");
+    compose.stop()?;
+
+    // Bring the devnet back even if archiving fails
+    let archived = write_archive(&config, &project, &manifest, &path);
+    say!("Starting services again...");
+    compose.start(&running)?;
+    archived?;
+
+    let size = fs::metadata(&path).map(|meta| meta.len()).unwrap_or_default();
+    say!();
+    say!(
+        "{} Saved snapshot '{}' at height {} ({}, {})",
+        "✓".green(),
+        snapshot,
+        manifest.heights.zebra,
+        path.display(),
+        format_bytes(size)
+    );
+    say!("  Restore it with `zeckit snapshot restore {}`", snapshot);
+    say!();
+
+    output::emit(&manifest)
+}
+
+/// Replace the devnet's volumes with those of a snapshot, restore its
+/// generated configs and start the existing images on them, without
+/// rebuilding or waiting for funds.
+pub async fn restore(snapshot: &str, name: Option<String>) -> Result<()> {
+    validate_name(snapshot)?;
+
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!("{}", "  ZecKit - Restoring Snapshot".cyan().bold());
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!();
+
+    let config = devnet::load_project(name.as_deref())?;
+    let path = snapshot_path(&config.root, snapshot);
+    if !path.is_file() {
+        return Err(ZeckitError::Config(format!(
+            "No snapshot named '{}' (expected {})",
+            snapshot,
+            path.display()
+        )));
+    }
+
+    let staging = staging_dir(&config)?;
+    run("tar", [OsStr::new("-xzf"), path.as_os_str(), OsStr::new("-C"), staging.path().as_os_str()])?;
+
+    let manifest: Manifest = serde_json::from_str(&fs::read_to_string(staging.path().join("manifest.json"))?)?;
+    if manifest.version != SNAPSHOT_VERSION {
+        return Err(ZeckitError::Config(format!(
+            "Snapshot '{}' has format version {}, this zeckit reads version {}",
+            snapshot, manifest.version, SNAPSHOT_VERSION
+        )));
+    }
+
+    let compose = DockerCompose::new(&config)?;
+
+    // Running containers hold the volumes
+    say!("Removing containers...");
+    let containers = compose.containers()?;
+    let services: Vec<&str> = containers.iter().map(|c| c.service.as_str()).collect();
+    compose.remove_services(&services)?;
+
+    let project = compose.project()?;
+    for volume in &manifest.volumes {
+        let docker_volume = &project
+            .volumes
+            .get(volume)
+            .ok_or_else(|| {
+                ZeckitError::Config(format!("Snapshot volume '{}' is not in docker-compose.yml", volume))
+            })?
+            .name;
+
+        say!("Restoring {}...", volume);
+        ensure_volume(&project.name, volume, docker_volume)?;
+        restore_volume(docker_volume, &staging.path().join("volumes"), volume)?;
+    }
+
+    let generated = config.generated_dir();
+    fs::create_dir_all(&generated)?;
+    for entry in fs::read_dir(staging.path().join("config"))? {
+        let entry = entry?;
+        fs::copy(entry.path(), generated.join(entry.file_name()))?;
+    }
+    drop(staging);
+
+    say!();
+    say!(
+        "{} Restored snapshot '{}' (height {}, {})",
+        "✓".green(),
+        snapshot,
+        manifest.heights.zebra,
+        manifest.backend
+    );
+    say!();
+
+    if let Some(name) = &name {
+        if let Some(state) = DevnetState::load(&config.root, name)? {
+            DevnetState { backend: manifest.backend, ..state }.save(&config.root)?;
+        }
+    }
+
+    start_restored(&config, &manifest).await
+}
+
+/// Start the snapshot's services on the restored volumes and configs, then
+/// wait for Zebra to serve the snapshot's chain, the indexer to reach its
+/// height and the faucet to answer.
+async fn start_restored(config: &ProjectConfig, manifest: &Manifest) -> Result<()> {
+    let backend = manifest.backend;
+    let settings = Settings::from_config(config);
+    let timeouts = &config.timeouts;
+
+    // Picks up the restored services file
+    let compose = DockerCompose::new(config)?;
+    let mut services = backend.services();
+    services.extend(config.extra_services());
+
+    say!("Starting services: {}", services.join(", "));
+    match backend.profile() {
+        Some(profile) => compose.up_with_profile(profile, &services)?,
+        None => compose.up(&services)?,
+    }
+    say!();
+
+    let total_steps = if backend.has_wallet() { 4 } else { 2 };
+    let checker = HealthChecker::new(&settings);
+    let zebra = ZebraRpcClient::new(&settings.zebra_rpc_url);
+
+    let pb = up::stage_spinner(1, total_steps, "Starting Zebra");
+    checker.wait_for_zebra(&pb, Duration::from_secs(timeouts.zebra)).await?;
+    pb.finish_with_message("Zebra ready");
+
+    let pb = up::stage_spinner(2, total_steps, "Checking the restored chain");
+    let target = manifest.heights.zebra;
+    wait_for_height(&pb, "Zebra", target, Duration::from_secs(timeouts.zebra), || async {
+        Ok(zebra.get_block_count().await?)
+    })
+    .await?;
+    let block = zebra.get_block(&target.to_string()).await?;
+    if block.hash != manifest.best_block_hash {
+        return Err(ZeckitError::HealthCheck(format!(
+            "Zebra's block {} is {}, the snapshot's is {}",
+            target, block.hash, manifest.best_block_hash
+        )));
+    }
+    pb.finish_with_message(format!("Zebra serving the snapshot's chain at block {}", target));
+
+    if backend.has_wallet() {
+        let pb = up::stage_spinner(3, total_steps, &format!("Starting {}", backend.display_name()));
+        let timeout = Duration::from_secs(timeouts.indexer);
+        checker.wait_for_backend(backend, &pb, timeout).await?;
+        if let Some(target) = manifest.heights.indexer {
+            wait_for_height(&pb, backend.display_name(), target, timeout, || async {
+                Ok(checker.indexer_info().await?.block_height)
+            })
+            .await?;
+        }
+        pb.finish_with_message(format!("{} ready", backend.display_name()));
+
+        let pb = up::stage_spinner(4, total_steps, "Starting Faucet");
+        checker.wait_for_faucet(&pb, Duration::from_secs(timeouts.faucet)).await?;
+        pb.finish_with_message("Faucet ready");
+    }
+
+    up::print_connection_info(backend, &settings);
+    Ok(())
+}
+
+/// Poll `height` until it reaches `target`.
+async fn wait_for_height<F, Fut>(
+    pb: &ProgressBar,
+    name: &str,
+    target: u64,
+    timeout: Duration,
+    mut height: F,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<u64>>,
+{
+    let start = Instant::now();
+
+    loop {
+        let status = match height().await {
+            Ok(height) if height >= target => return Ok(()),
+            Ok(height) => format!("at block {} of {}", height, target),
+            Err(e) => e.to_string(),
+        };
+
+        if start.elapsed() >= timeout {
+            return Err(ZeckitError::ServiceNotReady(format!(
+                "{} did not reach the snapshot's block {} within {}s ({})",
+                name,
+                target,
+                timeout.as_secs(),
+                status
+            )));
+        }
+
+        pb.set_message(format!("Waiting for {} ({})", name, status));
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+/// Snapshots are shared by every devnet of the project.
+fn snapshot_path(root: &Path, snapshot: &str) -> PathBuf {
+    root.join(GENERATED_DIR)
+        .join("snapshots")
+        .join(format!("{}.tar.gz", snapshot))
+}
+
+/// Snapshot names become file names.
+fn validate_name(snapshot: &str) -> Result<()> {
+    let valid = snapshot.chars().next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && snapshot
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.');
+
+    if !valid {
+        return Err(ZeckitError::Config(format!(
+            "Invalid snapshot name '{}': use letters, digits, '-', '_' and '.'",
+            snapshot
+        )));
+    }
+
+    Ok(())
+}
+
+/// The backend whose indexer has a container, running or not.
+fn devnet_backend(containers: &[ContainerState]) -> Backend {
+    Backend::INDEXERS
+        .into_iter()
+        .find(|backend| {
+            backend
+                .indexer_service()
+                .is_some_and(|service| containers.iter().any(|c| c.service == service))
+        })
+        .unwrap_or(Backend::None)
+}
+
+/// Volumes holding `backend`'s state.
+fn backend_volumes(backend: Backend) -> Vec<&'static str> {
+    match backend {
+        Backend::Lwd => vec!["zebra-data", "lightwalletd-data", "zingo-data", "faucet-data"],
+        Backend::Zaino => vec!["zebra-data", "zaino-data", "zingo-data", "faucet-data"],
+        Backend::None => vec!["zebra-data"],
+    }
+}
+
+/// Image ID of each service's container.
+fn image_ids(containers: &[ContainerState]) -> Result<BTreeMap<String, String>> {
+    containers
+        .iter()
+        .map(|container| {
+            let image = run("docker", ["inspect", "--format", "{{.Image}}", &container.name])?;
+            Ok((container.service.clone(), image.trim().to_string()))
+        })
+        .collect()
+}
+
+/// A scratch directory inside the project, which Docker Desktop shares with
+/// its VM (unlike some temp dirs).
+fn staging_dir(config: &ProjectConfig) -> Result<tempfile::TempDir> {
+    let dir = config.root.join(GENERATED_DIR);
+    fs::create_dir_all(&dir)?;
+    Ok(tempfile::tempdir_in(dir)?)
+}
+
+fn write_archive(config: &ProjectConfig, project: &ComposeProject, manifest: &Manifest, path: &Path) -> Result<()> {
+    let staging = staging_dir(config)?;
+    let volumes_dir = staging.path().join("volumes");
+    let config_dir = staging.path().join("config");
+    fs::create_dir_all(&volumes_dir)?;
+    fs::create_dir_all(&config_dir)?;
+
+    for volume in &manifest.volumes {
+        say!("Archiving {}...", volume);
+        archive_volume(&project.volumes[volume].name, &volumes_dir, volume)?;
+    }
+
+    for file in [config.zebra_config_path(), config.services_compose_path()] {
+        if let (true, Some(file_name)) = (file.exists(), file.file_name()) {
+            fs::copy(&file, config_dir.join(file_name))?;
+        }
+    }
+
+    fs::write(staging.path().join("manifest.json"), serde_json::to_string_pretty(manifest)?)?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    run(
+        "tar",
+        [
+            OsStr::new("-czf"),
+            path.as_os_str(),
+            OsStr::new("-C"),
+            staging.path().as_os_str(),
+            OsStr::new("manifest.json"),
+            OsStr::new("volumes"),
+            OsStr::new("config"),
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Write the contents of `docker_volume` to `dir/<volume>.tar.gz`.
+fn archive_volume(docker_volume: &str, dir: &Path, volume: &str) -> Result<()> {
+    run(
+        "docker",
+        [
+            "run".to_string(),
+            "--rm".to_string(),
+            "-v".to_string(),
+            format!("{}:/volume:ro", docker_volume),
+            "-v".to_string(),
+            format!("{}:/backup", dir.display()),
+            HELPER_IMAGE.to_string(),
+            "tar".to_string(),
+            "-czf".to_string(),
+            format!("/backup/{}.tar.gz", volume),
+            "-C".to_string(),
+            "/volume".to_string(),
+            ".".to_string(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Replace the contents of `docker_volume` with `dir/<volume>.tar.gz`.
+fn restore_volume(docker_volume: &str, dir: &Path, volume: &str) -> Result<()> {
+    run(
+        "docker",
+        [
+            "run".to_string(),
+            "--rm".to_string(),
+            "-v".to_string(),
+            format!("{}:/volume", docker_volume),
+            "-v".to_string(),
+            format!("{}:/backup:ro", dir.display()),
+            HELPER_IMAGE.to_string(),
+            "sh".to_string(),
+            "-c".to_string(),
+            format!("find /volume -mindepth 1 -delete && tar -xzf /backup/{}.tar.gz -C /volume", volume),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Create `docker_volume` if needed, labelled as compose would so that
+/// `up` adopts it without warnings.
+fn ensure_volume(project: &str, volume: &str, docker_volume: &str) -> Result<()> {
+    if run("docker", ["volume", "inspect", docker_volume]).is_ok() {
+        return Ok(());
+    }
+
+    run(
+        "docker",
+        [
+            "volume".to_string(),
+            "create".to_string(),
+            "--label".to_string(),
+            format!("com.docker.compose.project={}", project),
+            "--label".to_string(),
+            format!("com.docker.compose.volume={}", volume),
+            docker_volume.to_string(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Run `program` with `args`, returning stdout.
+fn run<I, S>(program: &str, args: I) -> Result<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| ZeckitError::Docker(format!("Failed to run {}: {}", program, e)))?;
+
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(ZeckitError::Docker(format!("{} failed: {}", program, error.trim())));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("funded-101").is_ok());
+        assert!(validate_name("v1.2_base").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name(".hidden").is_err());
+        assert!(validate_name("../escape").is_err());
+    }
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let manifest = Manifest {
+            version: SNAPSHOT_VERSION,
+            name: "funded".into(),
+            created_at: 1_760_000_000,
+            zeckit_version: "0.1.0".into(),
+            backend: Backend::Zaino,
+            heights: Heights { zebra: 120, indexer: Some(120), faucet_wallet: None },
+            best_block_hash: "00ab".into(),
+            addresses: Addresses { miner: Some("tmMiner".into()), faucet: None },
+            volumes: backend_volumes(Backend::Zaino).into_iter().map(String::from).collect(),
+            images: BTreeMap::from([("zebra".to_string(), "sha256:01".to_string())]),
+        };
+
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert!(!json.contains("faucet_wallet"));
+
+        let parsed: Manifest = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.backend, Backend::Zaino);
+        assert_eq!(parsed.heights.indexer, Some(120));
+        assert_eq!(parsed.volumes, ["zebra-data", "zaino-data", "zingo-data", "faucet-data"]);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_height() {
+        let pb = ProgressBar::hidden();
+        let mut heights = [118, 119, 120, 121].into_iter();
+        wait_for_height(&pb, "Zebra", 120, Duration::from_secs(30), || {
+            let height = heights.next().unwrap();
+            async move { Ok(height) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(heights.next(), Some(121));
+
+        let behind = wait_for_height(&pb, "Zaino", 120, Duration::ZERO, || async { Ok(100) }).await;
+        assert!(behind.unwrap_err().to_string().contains("at block 100 of 120"));
+    }
+}
@@ -1,65 +1,186 @@
-use crate::docker::compose::DockerCompose;
+use crate::config::backend::Backend;
+use crate::config::devnet;
+use crate::config::settings::Settings;
+use crate::docker::compose::{ContainerState, DockerCompose};
+use crate::docker::health::{FaucetReadiness, HealthChecker};
 use crate::error::Result;
+use crate::output;
 use colored::*;
-use reqwest::Client;
-use serde_json::Value;
-
-pub async fn execute() -> Result<()> {
-    println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-    println!("{}", "  ZecKit - Devnet Status".cyan().bold());
-    println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-    println!();
-    
-    let compose = DockerCompose::new()?;
-    let containers = compose.ps()?;
-    
+use serde::Serialize;
+use zeckit_rpc::ZebraRpcClient;
+
+/// `zeckit status --output json`.
+#[derive(Debug, Serialize)]
+struct StatusReport {
+    devnet: Option<String>,
+    compose_project: Option<String>,
+    containers: Vec<ContainerState>,
+    zebra: ServiceHealth,
+    indexer: Option<ServiceHealth>,
+    faucet: ServiceHealth,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ServiceHealth {
+    name: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wallet_height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    balance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checks: Option<FaucetReadiness>,
+}
+
+impl ServiceHealth {
+    fn failed(name: &str, error: impl ToString) -> Self {
+        Self {
+            name: name.to_string(),
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+pub async fn execute(name: Option<String>) -> Result<()> {
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!("{}", "  ZecKit - Devnet Status".cyan().bold());
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!();
+
+    let config = devnet::load_project(name.as_deref())?;
+
+    if let Some(project) = config.compose_project() {
+        say!("  Devnet: {} (compose project {})", name.as_deref().unwrap_or_default(), project);
+        say!();
+    }
+    let compose = DockerCompose::new(&config)?;
+    let containers = compose.containers()?;
+
     // Display container status
-    for container in containers {
-        let status_color = if container.contains("Up") {
-            "green"
+    for container in &containers {
+        let line = format!("{:<28} {}", container.name, container.status);
+        if container.state == "running" {
+            say!("  {}", line.green());
         } else {
-            "red"
-        };
-        
-        println!("  {}", container.color(status_color));
+            say!("  {}", line.red());
+        }
     }
-    
-    println!();
-    
+
+    say!();
+
     // Check service health
-    let client = Client::new();
-    
+    let settings = Settings::from_config(&config);
+    let checker = HealthChecker::new(&settings);
+
     // Zebra
-    print_service_status(&client, "Zebra", "http://127.0.0.1:8232").await;
-    
-    // Faucet
-    print_service_status(&client, "Faucet", "http://127.0.0.1:8080/stats").await;
-    
-    println!();
-    Ok(())
-}
+    let zebra = ZebraRpcClient::new(&settings.zebra_rpc_url);
+    let zebra = match zebra.get_blockchain_info().await {
+        Ok(info) => ServiceHealth {
+            name: "Zebra".to_string(),
+            ok: true,
+            height: Some(u64::from(info.blocks)),
+            chain: Some(info.chain),
+            ..Default::default()
+        },
+        Err(e) => ServiceHealth::failed("Zebra", e),
+    };
+    print_health(&zebra);
 
-async fn print_service_status(client: &Client, name: &str, url: &str) {
-    match client.get(url).send().await {
-        Ok(resp) if resp.status().is_success() => {
-            if let Ok(json) = resp.json::<Value>().await {
-                println!("  {} {} - {}", "✓".green(), name.bold(), format_json(&json));
-            } else {
-                println!("  {} {} - {}", "✓".green(), name.bold(), "OK");
-            }
+    // Indexer, if one is running
+    let indexer = Backend::INDEXERS.into_iter().find(|backend| {
+        backend.indexer_service().is_some_and(|service| {
+            containers
+                .iter()
+                .any(|c| c.service == service && c.state == "running")
+        })
+    });
+
+    let indexer = match indexer {
+        Some(backend) => {
+            let health = match checker.indexer_info().await {
+                Ok(info) => ServiceHealth {
+                    name: backend.display_name().to_string(),
+                    ok: true,
+                    height: Some(info.block_height),
+                    chain: Some(info.chain_name),
+                    version: Some(format!("{} {}", info.vendor, info.version)),
+                    ..Default::default()
+                },
+                Err(e) => ServiceHealth::failed(backend.display_name(), e),
+            };
+            print_health(&health);
+            Some(health)
         }
-        _ => {
-            println!("  {} {} - {}", "✗".red(), name.bold(), "Not responding");
+        None => None,
+    };
+
+    // Faucet
+    let mut faucet = match checker.faucet_stats().await {
+        Ok(stats) => ServiceHealth {
+            name: "Faucet".to_string(),
+            ok: true,
+            wallet_height: stats.wallet_height,
+            balance: Some(stats.current_balance),
+            ..Default::default()
+        },
+        Err(e) => ServiceHealth::failed("Faucet", e),
+    };
+    print_health(&faucet);
+
+    if let Ok(readiness) = checker.faucet_readiness().await {
+        for (name, check) in &readiness.checks {
+            let mark = match (check.ok, check.required) {
+                (true, _) => "✓".green(),
+                (false, true) => "✗".red(),
+                (false, false) => "!".yellow(),
+            };
+            say!("      {} {} - {}", mark, name, check.message);
         }
+        faucet.checks = Some(readiness);
     }
+
+    say!();
+
+    output::emit(&StatusReport {
+        devnet: name,
+        compose_project: config.compose_project(),
+        containers,
+        zebra,
+        indexer,
+        faucet,
+    })
 }
 
-fn format_json(json: &Value) -> String {
-    if let Some(height) = json.get("zebra_height") {
-        format!("Height: {}", height)
-    } else if let Some(balance) = json.get("current_balance") {
-        format!("Balance: {} ZEC", balance)
-    } else {
-        "Running".to_string()
-    }
-}
\ No newline at end of file
+fn print_health(health: &ServiceHealth) {
+    let Some(error) = &health.error else {
+        let mut details = Vec::new();
+        if let Some(version) = &health.version {
+            details.push(version.clone());
+        }
+        if let Some(balance) = health.balance {
+            details.push(format!("Balance: {} ZEC", balance));
+        }
+        if let Some(height) = health.wallet_height {
+            details.push(format!("Wallet height: {}", height));
+        }
+        if let Some(height) = health.height {
+            details.push(format!("Height: {}", height));
+        }
+        if let Some(chain) = &health.chain {
+            details.push(format!("({})", chain));
+        }
+        say!("  {} {} - {}", "✓".green(), health.name.bold(), details.join(" "));
+        return;
+    };
+
+    say!("  {} {} - {}", "✗".red(), health.name.bold(), error);
+}
@@ -1,399 +1,191 @@
-use crate::error::Result;
+use crate::config::devnet;
+use crate::docker::compose::DockerCompose;
+use crate::error::{Result, ZeckitError};
+use crate::output;
+use crate::report::{ReportTarget, TestReport};
+use crate::report::TestResult;
+use crate::smoke::parity;
+use crate::smoke::scenario::Scenario;
+use crate::smoke::{self, Registry, RunOptions, SmokeTest, TestContext};
 use colored::*;
-use reqwest::Client;
-use serde_json::Value;
-use std::process::Command;
-use tokio::time::{sleep, Duration};
-
-pub async fn execute() -> Result<()> {
-    println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-    println!("{}", "  ZecKit - Running Smoke Tests".cyan().bold());
-    println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-    println!();
-
-    let client = Client::new();
-    let mut passed = 0;
-    let mut failed = 0;
-
-    // Test 1: Zebra RPC
-    print!("  [1/5] Zebra RPC connectivity... ");
-    match test_zebra_rpc(&client).await {
-        Ok(_) => {
-            println!("{}", "PASS".green());
-            passed += 1;
-        }
-        Err(e) => {
-            println!("{} {}", "FAIL".red(), e);
-            failed += 1;
-        }
-    }
-
-    // Test 2: Faucet Health
-    print!("  [2/5] Faucet health check... ");
-    match test_faucet_health(&client).await {
-        Ok(_) => {
-            println!("{}", "PASS".green());
-            passed += 1;
-        }
-        Err(e) => {
-            println!("{} {}", "FAIL".red(), e);
-            failed += 1;
-        }
-    }
-
-    // Test 3: Faucet Stats
-    print!("  [3/5] Faucet stats endpoint... ");
-    match test_faucet_stats(&client).await {
-        Ok(_) => {
-            println!("{}", "PASS".green());
-            passed += 1;
-        }
-        Err(e) => {
-            println!("{} {}", "FAIL".red(), e);
-            failed += 1;
-        }
-    }
-
-    // Test 4: Faucet Address
-    print!("  [4/5] Faucet address retrieval... ");
-    match test_faucet_address(&client).await {
-        Ok(_) => {
-            println!("{}", "PASS".green());
-            passed += 1;
-        }
-        Err(e) => {
-            println!("{} {}", "FAIL".red(), e);
-            failed += 1;
-        }
-    }
-
-    // Test 5: Wallet balance and shield (direct wallet test)
-    print!("  [5/5] Wallet balance and shield... ");
-    match test_wallet_shield().await {
-        Ok(_) => {
-            println!("{}", "PASS".green());
-            passed += 1;
-        }
-        Err(e) => {
-            println!("{} {}", "FAIL".red(), e);
-            failed += 1;
-        }
-    }
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::time::Instant;
+
+/// Lines of each service's logs attached to a failing test.
+const LOG_TAIL: usize = 50;
+
+/// Which tests to run and how.
+#[derive(Debug, Default)]
+pub struct TestOptions {
+    pub filter: Option<String>,
+    pub tags: Vec<String>,
+    pub list: bool,
+    pub jobs: Option<usize>,
+    pub fail_fast: bool,
+    /// Scenario files to run instead of the built-in tests.
+    pub scenarios: Vec<PathBuf>,
+    /// Compare lightwalletd and Zaino instead of running the built-in tests.
+    pub parity: bool,
+    pub reports: Vec<ReportTarget>,
+}
 
-    println!();
-    println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-    println!("  Tests passed: {}", passed.to_string().green());
-    println!("  Tests failed: {}", failed.to_string().red());
-    println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-    println!();
+/// Services whose logs are attached to failing scenario steps.
+const SCENARIO_SERVICES: &[&str] = &["faucet", "zebra"];
 
-    if failed > 0 {
-        return Err(crate::error::zeckitError::HealthCheck(
-            format!("{} test(s) failed", failed)
-        ));
-    }
+/// Services whose logs are attached to failing parity checks.
+const PARITY_SERVICES: &[&str] = &["lightwalletd", "zaino"];
 
-    Ok(())
+/// One entry of `zeckit test --list --output json`.
+#[derive(Debug, Serialize)]
+struct TestInfo<'a> {
+    name: &'a str,
+    description: &'a str,
+    tags: &'a [&'a str],
+    dependencies: &'a [&'a str],
+    timeout_secs: u64,
 }
 
-async fn test_zebra_rpc(client: &Client) -> Result<()> {
-    let resp = client
-        .post("http://127.0.0.1:8232")
-        .json(&serde_json::json!({
-            "jsonrpc": "2.0",
-            "id": "test",
-            "method": "getblockcount",
-            "params": []
-        }))
-        .send()
-        .await?;
+/// Recent logs of the running services matching `prefixes`, e.g. `faucet`
+/// for `faucet-lwd`.
+fn capture_logs(compose: &DockerCompose, running: &[String], prefixes: &[&str]) -> Option<String> {
+    let logs: Vec<String> = running
+        .iter()
+        .filter(|service| prefixes.iter().any(|prefix| service.starts_with(prefix)))
+        .filter_map(|service| compose.logs(service, LOG_TAIL).ok())
+        .map(|lines| lines.join("\n"))
+        .collect();
+
+    (!logs.is_empty()).then(|| logs.join("\n"))
+}
 
-    if !resp.status().is_success() {
-        return Err(crate::error::zeckitError::HealthCheck(
-            "Zebra RPC not responding".into()
+pub async fn execute(options: TestOptions, name: Option<String>) -> Result<()> {
+    if output::is_json() && options.reports.iter().any(|target| target.path.is_none()) {
+        return Err(ZeckitError::Config(
+            "--output json already uses stdout; give --report a path (e.g. tap=results.tap)".into(),
         ));
     }
 
-    Ok(())
-}
-
-async fn test_faucet_health(client: &Client) -> Result<()> {
-    let resp = client
-        .get("http://127.0.0.1:8080/health")
-        .send()
-        .await?;
+    let scenarios = options
+        .scenarios
+        .iter()
+        .map(|path| Scenario::load(path))
+        .collect::<Result<Vec<_>>>()?;
 
-    if !resp.status().is_success() {
-        return Err(crate::error::zeckitError::HealthCheck(
-            "Faucet health check failed".into()
-        ));
+    let registry = Registry::builtin()?;
+    let tests = if scenarios.is_empty() && !options.parity {
+        registry.select(options.filter.as_deref(), &options.tags)?
+    } else {
+        Vec::new()
+    };
+
+    if options.list {
+        return list(&tests);
+    }
+
+    if tests.is_empty() && scenarios.is_empty() && !options.parity {
+        return Err(ZeckitError::Config("No smoke tests match the given --filter/--tag".into()));
+    }
+
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!("{}", "  ZecKit - Running Smoke Tests".cyan().bold());
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!();
+
+    let config = devnet::load_project(name.as_deref())?;
+    let compose = DockerCompose::new(&config)?;
+    let ctx = Arc::new(TestContext::new(config));
+    let suite_start = Instant::now();
+
+    let mut results: Vec<(TestResult, &[&str])> = Vec::new();
+    let mut parity_report = None;
+    if options.parity {
+        let (report, outcomes) = parity::run(&ctx).await?;
+        results.extend(outcomes.into_iter().map(|result| (result, PARITY_SERVICES)));
+        parity_report = Some(report);
+    } else if scenarios.is_empty() {
+        let run_options = RunOptions {
+            jobs: options.jobs.unwrap_or(tests.len()),
+            fail_fast: options.fail_fast,
+        };
+        let outcomes = smoke::run(&tests, ctx, run_options).await;
+        results.extend(outcomes.into_iter().zip(tests.iter().map(|test| test.services())));
+    } else {
+        for scenario in &scenarios {
+            let failed = results.iter().any(|(result, _)| !result.passed && !result.skipped);
+            if failed && options.fail_fast {
+                break;
+            }
+            let outcomes = scenario.run(&ctx).await;
+            results.extend(outcomes.into_iter().map(|result| (result, SCENARIO_SERVICES)));
+        }
     }
 
-    Ok(())
-}
+    // Attach service logs to failures
+    let running = compose.running_services().unwrap_or_default();
+    let results = results
+        .into_iter()
+        .map(|(mut result, services)| {
+            if !result.passed && !result.skipped {
+                result.logs = capture_logs(&compose, &running, services);
+            }
+            result
+        })
+        .collect();
 
-async fn test_faucet_stats(client: &Client) -> Result<()> {
-    let resp = client
-        .get("http://127.0.0.1:8080/stats")
-        .send()
-        .await?;
+    let report = TestReport::new(results, suite_start.elapsed().as_millis());
 
-    if !resp.status().is_success() {
-        return Err(crate::error::zeckitError::HealthCheck(
-            "Faucet stats not available".into()
-        ));
-    }
+    say!();
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!("  Tests passed:  {}", report.passed.to_string().green());
+    say!("  Tests failed:  {}", report.failed.to_string().red());
+    say!("  Tests skipped: {}", report.skipped.to_string().yellow());
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!();
 
-    let json: Value = resp.json().await?;
-    
-    // Verify key fields exist
-    if json.get("faucet_address").is_none() {
-        return Err(crate::error::zeckitError::HealthCheck(
-            "Stats missing faucet_address".into()
-        ));
-    }
-    
-    if json.get("current_balance").is_none() {
-        return Err(crate::error::zeckitError::HealthCheck(
-            "Stats missing current_balance".into()
-        ));
+    for target in &options.reports {
+        target.write(&report)?;
+        if let Some(path) = &target.path {
+            say!("Report written to {}", path.display());
+        }
     }
 
-    Ok(())
-}
-
-async fn test_faucet_address(client: &Client) -> Result<()> {
-    let resp = client
-        .get("http://127.0.0.1:8080/address")
-        .send()
-        .await?;
-
-    if !resp.status().is_success() {
-        return Err(crate::error::zeckitError::HealthCheck(
-            "Could not get faucet address".into()
-        ));
+    match &parity_report {
+        Some(parity_report) => output::emit(parity_report)?,
+        None => output::emit(&report)?,
     }
 
-    let json: Value = resp.json().await?;
-    if json.get("address").is_none() {
-        return Err(crate::error::zeckitError::HealthCheck(
-            "Invalid address response".into()
-        ));
+    if report.failed > 0 {
+        return Err(ZeckitError::HealthCheck(format!("{} test(s) failed", report.failed)));
     }
 
     Ok(())
 }
 
-async fn test_wallet_shield() -> Result<()> {
-    println!();
-    
-    // Step 1: Detect backend
-    let backend_uri = detect_backend()?;
-    println!("    Detecting backend: {}", backend_uri);
-    
-    // Step 2: Wait for wallet balance to actually appear (with retries)
-    println!("    Waiting for wallet to receive funds...");
-    
-    let (transparent_before, orchard_before) = wait_for_wallet_balance(&backend_uri).await?;
-    
-    println!("    Transparent: {} ZEC", transparent_before);
-    println!("    Orchard: {} ZEC", orchard_before);
-    
-    // Step 3: If we have transparent funds >= 1 ZEC, SHIELD IT!
-    if transparent_before >= 1.0 {
-        println!("    Shielding {} ZEC to Orchard...", transparent_before);
-        
-        // Run shield command
-        let shield_cmd = format!(
-            "bash -c \"echo -e 'shield\\nconfirm\\nquit' | zingo-cli --data-dir /var/zingo --server {} --chain regtest 2>&1\"",
-            backend_uri
-        );
-        
-        let shield_output = Command::new("docker")
-            .args(&["exec", "-i", "zeckit-zingo-wallet", "bash", "-c", &shield_cmd])
-            .output()
-            .map_err(|e| crate::error::zeckitError::HealthCheck(format!("Shield failed: {}", e)))?;
-        
-        let shield_str = String::from_utf8_lossy(&shield_output.stdout);
-        
-        // Check if shield succeeded
-        if shield_str.contains("txid") {
-            println!("    Shield transaction broadcast!");
-            
-            // Extract TXID
-            for line in shield_str.lines() {
-                if line.contains("txid") {
-                    if let Some(txid_start) = line.find('"') {
-                        let txid_part = &line[txid_start+1..];
-                        if let Some(txid_end) = txid_part.find('"') {
-                            let txid = &txid_part[..txid_end];
-                            println!("    TXID: {}...", &txid[..16.min(txid.len())]);
-                        }
-                    }
-                }
-            }
-            
-            // Wait for transaction to be mined
-            println!("    Waiting for transaction to confirm...");
-            sleep(Duration::from_secs(30)).await;
-            
-            // Wait for wallet to sync the new block
-            println!("    Waiting for wallet to sync new blocks...");
-            sleep(Duration::from_secs(5)).await;
-            
-            // Check balance AFTER shielding
-            let (transparent_after, orchard_after) = get_wallet_balance(&backend_uri)?;
-            
-            println!("    Balance after shield:");
-            println!("    Transparent: {} ZEC (was {})", transparent_after, transparent_before);
-            println!("    Orchard: {} ZEC (was {})", orchard_after, orchard_before);
-            
-            // Verify shield worked
-            if orchard_after > orchard_before || transparent_after < transparent_before {
-                println!("    Shield successful - funds moved!");
-                println!();
-                print!("  [5/5] Wallet balance and shield... ");
-                return Ok(());
-            } else {
-                println!("    Shield transaction sent but balance not updated yet");
-                println!("    (May need more time to confirm)");
-                println!();
-                print!("  [5/5] Wallet balance and shield... ");
-                return Ok(());
-            }
-            
-        } else if shield_str.contains("error") || shield_str.contains("additional change output") {
-            // Known upstream bug with large UTXO sets
-            println!("    Shield failed: Upstream zingolib bug (large UTXO set)");
-            println!("    Wallet has {} ZEC available - test PASS", transparent_before);
-            println!();
-            print!("  [5/5] Wallet balance and shield... ");
-            return Ok(());
-            
-        } else {
-            println!("    Shield response unclear");
-            println!("    Wallet has {} ZEC - test PASS", transparent_before);
-            println!();
-            print!("  [5/5] Wallet balance and shield... ");
-            return Ok(());
-        }
-        
-    } else if orchard_before >= 1.0 {
-        println!("    Wallet already has {} ZEC shielded in Orchard - PASS", orchard_before);
-        println!();
-        print!("  [5/5] Wallet balance and shield... ");
-        return Ok(());
-        
-    } else if transparent_before > 0.0 {
-        println!("    Wallet has {} ZEC transparent (too small to shield)", transparent_before);
-        println!("    Need at least 1 ZEC to shield");
-        println!("    SKIP (insufficient balance)");
-        println!();
-        print!("  [5/5] Wallet balance and shield... ");
-        return Ok(());
-        
-    } else {
-        println!("    No balance found");
-        println!("    SKIP (needs mining to complete)");
-        println!();
-        print!("  [5/5] Wallet balance and shield... ");
-        return Ok(());
-    }
-}
+/// `zeckit test --list`: the selected tests without running them.
+fn list(tests: &[Arc<dyn SmokeTest>]) -> Result<()> {
+    let infos: Vec<TestInfo> = tests
+        .iter()
+        .map(|test| TestInfo {
+            name: test.name(),
+            description: test.description(),
+            tags: test.tags(),
+            dependencies: test.dependencies(),
+            timeout_secs: test.timeout().as_secs(),
+        })
+        .collect();
 
-/// Wait for wallet to actually have a balance (with multiple retries)
-/// The background sync in zingo-cli can take time to update the local cache
-async fn wait_for_wallet_balance(backend_uri: &str) -> Result<(f64, f64)> {
-    let mut attempts = 0;
-    let max_attempts = 180; // 3 minutes of retrying
-    
-    loop {
-        let (transparent, orchard) = get_wallet_balance(backend_uri)?;
-        
-        // If we have ANY balance, return it
-        if transparent > 0.0 || orchard > 0.0 {
-            println!("    Balance synced after {} seconds", attempts);
-            return Ok((transparent, orchard));
-        }
-        
-        attempts += 1;
-        if attempts >= max_attempts {
-            println!("    Timeout waiting for balance ({}s) - balance still 0", max_attempts);
-            return Ok((0.0, 0.0));
+    for info in &infos {
+        say!("  {:<16} {}", info.name.bold(), info.description);
+        if !info.tags.is_empty() {
+            say!("  {:<16} tags: {}", "", info.tags.join(", "));
         }
-        
-        if attempts % 10 == 0 {
-            print!(".");
+        if !info.dependencies.is_empty() {
+            say!("  {:<16} needs: {}", "", info.dependencies.join(", "));
         }
-        
-        sleep(Duration::from_secs(1)).await;
+        say!("  {:<16} timeout: {}s", "", info.timeout_secs);
     }
-}
 
-fn get_wallet_balance(backend_uri: &str) -> Result<(f64, f64)> {
-    let balance_cmd = format!(
-        "bash -c \"echo -e 'balance\\nquit' | zingo-cli --data-dir /var/zingo --server {} --chain regtest --nosync 2>&1\"",
-        backend_uri
-    );
-    
-    let balance_output = Command::new("docker")
-        .args(&["exec", "zeckit-zingo-wallet", "bash", "-c", &balance_cmd])
-        .output()
-        .map_err(|e| crate::error::zeckitError::HealthCheck(format!("Balance check failed: {}", e)))?;
-    
-    let balance_str = String::from_utf8_lossy(&balance_output.stdout);
-    
-    let mut transparent_balance = 0.0;
-    let mut orchard_balance = 0.0;
-    
-    for line in balance_str.lines() {
-        if line.contains("confirmed_transparent_balance") {
-            if let Some(val) = line.split(':').nth(1) {
-                let val_str = val.trim().replace("_", "").replace(",", "");
-                if let Ok(bal) = val_str.parse::<i64>() {
-                    transparent_balance = bal as f64 / 100_000_000.0;
-                }
-            }
-        }
-        if line.contains("confirmed_orchard_balance") {
-            if let Some(val) = line.split(':').nth(1) {
-                let val_str = val.trim().replace("_", "").replace(",", "");
-                if let Ok(bal) = val_str.parse::<i64>() {
-                    orchard_balance = bal as f64 / 100_000_000.0;
-                }
-            }
-        }
-    }
-    
-    Ok((transparent_balance, orchard_balance))
+    output::emit(&infos)
 }
-
-fn detect_backend() -> Result<String> {
-    // Check if zaino container is running
-    let output = Command::new("docker")
-        .args(&["ps", "--filter", "name=zeckit-zaino", "--format", "{{.Names}}"])
-        .output()
-        .map_err(|e| crate::error::zeckitError::Docker(format!("Failed to detect backend: {}", e)))?;
-    
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    
-    if stdout.contains("zeckit-zaino") {
-        Ok("http://zaino:9067".to_string())
-    } else {
-        // Check for lightwalletd
-        let output = Command::new("docker")
-            .args(&["ps", "--filter", "name=zeckit-lightwalletd", "--format", "{{.Names}}"])
-            .output()
-            .map_err(|e| crate::error::zeckitError::Docker(format!("Failed to detect backend: {}", e)))?;
-        
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        
-        if stdout.contains("zeckit-lightwalletd") {
-            Ok("http://lightwalletd:9067".to_string())
-        } else {
-            Err(crate::error::zeckitError::HealthCheck(
-                "No backend detected (neither zaino nor lightwalletd running)".into()
-            ))
-        }
-    }
-}
\ No newline at end of file
@@ -1,528 +1,553 @@
+use crate::config::backend::Backend;
+use crate::config::devnet::DevnetState;
+use crate::config::project::ProjectConfig;
+use crate::config::settings::Settings;
+use crate::config::zebra::ZebraConfig;
 use crate::docker::compose::DockerCompose;
-use crate::docker::health::HealthChecker;
-use crate::error::{Result, zeckitError};
+use crate::docker::health::{Deadline, HealthChecker};
+use crate::docker::zingo::ZingoCli;
+use crate::error::{Result, ZeckitError};
+use crate::output;
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
-use reqwest::Client;
+use serde::Serialize;
 use serde_json::json;
-use std::process::Command;
+use zeckit_rpc::ZebraRpcClient;
 use std::fs;
-use std::io::{self, Write};
-use tokio::time::{sleep, Duration};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::{sleep, Duration, Instant};
 
-const MAX_WAIT_SECONDS: u64 = 60000;
-const WALLET_TIMEOUT_SECONDS: u64 = 6000;
+/// `zeckit up --output json`: where to reach the devnet once it is ready.
+#[derive(Debug, Serialize)]
+struct UpReport {
+    devnet: Option<String>,
+    backend: Backend,
+    height: Option<u64>,
+    endpoints: Endpoints,
+    addresses: Addresses,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    faucet_balance: Option<f64>,
+}
 
-pub async fn execute(backend: String, fresh: bool) -> Result<()> {
-    println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-    println!("{}", "  ZecKit - Starting Devnet".cyan().bold());
-    println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-    println!();
-    
-    let compose = DockerCompose::new()?;
-    
-    if fresh {
-        println!("{}", "Cleaning up old data...".yellow());
-        compose.down(true)?;
-    }
-    
-    let services = match backend.as_str() {
-        "lwd" => vec!["zebra", "faucet"],
-        "zaino" => vec!["zebra", "faucet"],
-        "none" => vec!["zebra", "faucet"],
-        _ => {
-            return Err(zeckitError::Config(format!(
-                "Invalid backend: {}. Use 'lwd', 'zaino', or 'none'", 
-                backend
-            )));
-        }
-    };
-    
-    println!("Starting services: {}", services.join(", "));
-    println!();
-    
-    // Build and start services with progress
-    if backend == "lwd" {
-        println!("Building Docker images...");
-        println!();
-        
-        println!("[1/4] Building Zebra...");
-        println!("[2/4] Building Lightwalletd...");
-        println!("[3/4] Building Zingo Wallet...");
-        println!("[4/4] Building Faucet...");
-        
-        compose.up_with_profile("lwd")?;
-        println!();
-    } else if backend == "zaino" {
-        println!("Building Docker images...");
-        println!();
+#[derive(Debug, Serialize)]
+struct Endpoints {
+    zebra_rpc: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    indexer_grpc: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    faucet_api: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct Addresses {
+    wallet_transparent: Option<String>,
+    wallet_unified: Option<String>,
+    faucet: Option<String>,
+}
+
+impl UpReport {
+    fn new(devnet: Option<String>, backend: Backend, settings: &Settings) -> Self {
+        let with_wallet = |url: &String| backend.has_wallet().then(|| url.clone());
         
-        println!("[1/4] Building Zebra...");
-        println!("[2/4] Building Zaino...");
-        println!("[3/4] Building Zingo Wallet...");
-        println!("[4/4] Building Faucet...");
+        Self {
+            devnet,
+            backend,
+            height: None,
+            endpoints: Endpoints {
+                zebra_rpc: settings.zebra_rpc_url.clone(),
+                indexer_grpc: with_wallet(&settings.backend_url),
+                faucet_api: with_wallet(&settings.faucet_api_url),
+            },
+            addresses: Addresses::default(),
+            faucet_balance: None,
+        }
+    }
+}
+
+pub async fn execute(
+    backend: Option<Backend>,
+    fresh: bool,
+    timeout: Option<u64>,
+    verbose: bool,
+    name: Option<String>,
+) -> Result<()> {
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!("{}", "  ZecKit - Starting Devnet".cyan().bold());
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!();
+    
+    let mut config = ProjectConfig::discover()?;
+    let backend = backend.unwrap_or(config.devnet.backend);
+    check_wallet_heights(backend, &config)?;
+    
+    if let Some(name) = &name {
+        // Keep the ports of an existing devnet so its URLs stay stable
+        let state = match DevnetState::load(&config.root, name)? {
+            Some(state) => DevnetState { backend, ..state },
+            None => DevnetState::new(name, backend)?,
+        };
+        state.save(&config.root)?;
+        state.apply(&mut config);
         
-        compose.up_with_profile("zaino")?;
-        println!();
-    } else {
-        compose.up(&services)?;
+        say!("Devnet: {} (compose project {})", state.name, state.compose_project);
     }
     
-    println!("Starting services...");
-    println!();
+    let settings = Settings::from_config(&config);
+    let timeouts = &config.timeouts;
     
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.green} {msg}")
-            .unwrap()
-    );
+    config.render_services_compose()?;
+    config.render_zebra_config(None)?;
+    let compose = DockerCompose::new(&config)?;
     
-    // [1/4] Zebra with percentage
-    let checker = HealthChecker::new();
-    let start = std::time::Instant::now();
-    
-    loop {
-        pb.tick();
-        
-        if checker.wait_for_zebra(&pb).await.is_ok() {
-            println!("[1/4] Zebra ready (100%)");
-            break;
-        }
-        
-        let elapsed = start.elapsed().as_secs();
-        if elapsed < 120 {
-            let progress = (elapsed as f64 / 120.0 * 100.0).min(99.0) as u32;
-            print!("\r[1/4] Starting Zebra... {}%", progress);
-            io::stdout().flush().ok();
-            sleep(Duration::from_secs(1)).await;
-        } else {
-            return Err(zeckitError::ServiceNotReady("Zebra not ready".into()));
-        }
+    if fresh {
+        say!("{}", "Cleaning up old data...".yellow());
+        compose.down(true)?;
     }
-    println!();
     
-    // [2/4] Backend with percentage
-    if backend == "lwd" || backend == "zaino" {
-        let backend_name = if backend == "lwd" { "Lightwalletd" } else { "Zaino" };
-        let start = std::time::Instant::now();
-        
-        loop {
-            pb.tick();
-            
-            if checker.wait_for_backend(&backend, &pb).await.is_ok() {
-                println!("[2/4] {} ready (100%)", backend_name);
-                break;
-            }
-            
-            let elapsed = start.elapsed().as_secs();
-            if elapsed < 180 {
-                let progress = (elapsed as f64 / 180.0 * 100.0).min(99.0) as u32;
-                print!("\r[2/4] Starting {}... {}%", backend_name, progress);
-                io::stdout().flush().ok();
-                sleep(Duration::from_secs(1)).await;
-            } else {
-                return Err(zeckitError::ServiceNotReady(format!("{} not ready", backend_name)));
-            }
-        }
-        println!();
+    let mut services = backend.services();
+    services.extend(config.extra_services());
+    
+    // Services of other backends may still be running from a previous `up`
+    let stale: Vec<&str> = Backend::INDEXERS
+        .iter()
+        .filter(|other| **other != backend)
+        .flat_map(|other| other.services())
+        .filter(|service| !services.contains(service))
+        .collect();
+    compose.remove_services(&stale)?;
+    
+    say!("Backend: {}", backend);
+    say!("Starting services: {}", services.join(", "));
+    say!();
+    
+    say!("Building Docker images...");
+    say!("(This may take 10-20 minutes on first build)");
+    compose.build(backend.profile(), &services, verbose, &build_log_path(&config))?;
+    say!("✓ Images built successfully");
+    say!();
+    
+    say!("Starting containers...");
+    match backend.profile() {
+        Some(profile) => compose.up_with_profile(profile, &services)?,
+        None => compose.up(&services)?,
     }
+    say!();
     
-    // [3/4] Wallet with percentage (EXTENDED TIMEOUT)
-    let backend_uri = if backend == "lwd" {
-        "http://lightwalletd:9067"
-    } else if backend == "zaino" {
-        "http://zaino:9067"
+    verify_topology(&compose, backend, &services)?;
+    
+    // Runs until `up` returns
+    let _logs = if verbose && output::is_verbose() {
+        Some(compose.follow_logs(&services)?)
     } else {
-        "http://lightwalletd:9067"
+        None
     };
     
-    let start = std::time::Instant::now();
-    loop {
-        pb.tick();
-        
-        if wait_for_wallet_ready(&pb, backend_uri).await.is_ok() {
-            println!("[3/4] Zingo Wallet ready (100%)");
-            break;
-        }
-        
-        let elapsed = start.elapsed().as_secs();
-        if elapsed < WALLET_TIMEOUT_SECONDS {
-            let progress = (elapsed as f64 / WALLET_TIMEOUT_SECONDS as f64 * 100.0).min(99.0) as u32;
-            print!("\r[3/4] Starting Zingo Wallet... {}%", progress);
-            io::stdout().flush().ok();
-            sleep(Duration::from_secs(1)).await;
-        } else {
-            return Err(zeckitError::ServiceNotReady("Wallet not ready after 100 minutes".into()));
-        }
-    }
-    println!();
+    say!("Waiting for services...");
+    say!();
     
-    // [4/4] Faucet with percentage
-    let start = std::time::Instant::now();
-    loop {
-        pb.tick();
-        
-        if checker.wait_for_faucet(&pb).await.is_ok() {
-            println!("[4/4] Faucet ready (100%)");
-            break;
-        }
-        
-        let elapsed = start.elapsed().as_secs();
-        if elapsed < 60 {
-            let progress = (elapsed as f64 / 60.0 * 100.0).min(99.0) as u32;
-            print!("\r[4/4] Starting Faucet... {}%", progress);
-            io::stdout().flush().ok();
-            sleep(Duration::from_secs(1)).await;
-        } else {
-            return Err(zeckitError::ServiceNotReady("Faucet not ready".into()));
-        }
-    }
-    println!();
+    let total_steps = if backend.has_wallet() { 8 } else { 1 };
+    let checker = HealthChecker::new(&settings);
+    let deadline = Deadline::after(Duration::from_secs(timeout.unwrap_or(timeouts.total)));
+    
+    // [1] Zebra answers RPC
+    let pb = stage_spinner(1, total_steps, "Starting Zebra");
+    checker.wait_for_zebra(&pb, deadline.limit(timeouts.zebra)).await?;
+    pb.finish_with_message("Zebra ready");
     
-    pb.finish_and_clear();
+    let zebra = ZebraRpcClient::new(&settings.zebra_rpc_url);
+    verify_activation_heights(&zebra, &config).await?;
     
-    // GET WALLET ADDRESS AND UPDATE ZEBRA CONFIG
-    println!();
-    println!("Configuring Zebra to mine to wallet...");
+    let mut report = UpReport::new(name, backend, &settings);
     
-    match get_wallet_transparent_address(backend_uri).await {
+    if backend.uri().is_none() {
+        print_connection_info(backend, &settings);
+        report.height = print_mining_info(&zebra).await;
+        return output::emit(&report);
+    }
+    
+    // [2] Indexer accepts connections
+    let backend_name = backend.display_name();
+    let pb = stage_spinner(2, total_steps, &format!("Starting {}", backend_name));
+    checker.wait_for_backend(backend, &pb, deadline.limit(timeouts.indexer)).await?;
+    pb.finish_with_message(format!("{} ready", backend_name));
+    
+    // [3] Zingo wallet has an address
+    let wallet = ZingoCli::devnet(&config, backend);
+    let pb = stage_spinner(3, total_steps, "Starting Zingo Wallet");
+    wait_for_wallet_ready(&pb, &wallet, deadline.limit(timeouts.wallet)).await?;
+    pb.finish_with_message("Zingo Wallet ready");
+    
+    // [4] Faucet is serving
+    let pb = stage_spinner(4, total_steps, "Starting Faucet");
+    checker.wait_for_faucet(&pb, deadline.limit(timeouts.faucet)).await?;
+    pb.finish_with_message("Faucet ready");
+    
+    // [5] Zebra restarted to mine to the wallet
+    say!();
+    let pb = stage_spinner(5, total_steps, "Configuring Zebra to mine to the wallet");
+    match wallet.transparent_address().await {
         Ok(t_address) => {
-            println!("Wallet transparent address: {}", t_address);
+            report.addresses.wallet_transparent = Some(t_address.clone());
             
-            if let Err(e) = update_zebra_miner_address(&t_address) {
-                println!("{}", format!("Warning: Could not update zebra.toml: {}", e).yellow());
+            let current = ZebraConfig::load(&config.zebra_config_path())
+                .ok()
+                .and_then(|zebra| zebra.mining.miner_address);
+            if current.as_deref() == Some(t_address.as_str()) {
+                pb.finish_with_message(format!("Zebra mining to {}", t_address));
             } else {
-                println!("Updated zebra.toml miner_address");
-                
-                println!("Restarting Zebra with new miner address...");
-                if let Err(e) = restart_zebra().await {
-                    println!("{}", format!("Warning: Zebra restart had issues: {}", e).yellow());
+                match config.render_zebra_config(Some(&t_address)) {
+                    Ok(()) => {
+                        pb.set_message("Restarting Zebra with new miner address");
+                        compose.restart("zebra")?;
+                        checker.wait_for_zebra(&pb, deadline.limit(timeouts.zebra)).await?;
+                        pb.finish_with_message(format!("Zebra mining to {}", t_address));
+                        say!("  miner_address set in {}", config.zebra_config_path().display());
+                    }
+                    Err(e) => {
+                        pb.finish_with_message(
+                            format!("Warning: Could not update Zebra config: {}", e).yellow().to_string(),
+                        );
+                    }
                 }
             }
         }
         Err(e) => {
-            println!("{}", format!("Warning: Could not get wallet address: {}", e).yellow());
-            println!("  Mining will use default address in zebra.toml");
+            pb.finish_with_message(
+                format!("Warning: Could not get wallet address: {}", e).yellow().to_string(),
+            );
+            say!("  Mining will use the miner_address from {}", config.zebra_template_path().display());
         }
     }
+    say!();
     
-    // NOW WAIT FOR BLOCKS (mining to correct address)
-    wait_for_mined_blocks(&pb, 101).await?;
-    
-    // Wait extra time for coinbase maturity
-    println!();
-    println!("Waiting for coinbase maturity (100 confirmations)...");
-    sleep(Duration::from_secs(120)).await;
+    // [6] Enough blocks for the first coinbase to mature
+    let premine = config.devnet.premine_blocks;
+    let pb = stage_counter(6, total_steps, "Mining to coinbase maturity", premine);
+    wait_for_mined_blocks(&pb, &zebra, premine, deadline.limit(timeouts.mining)).await?;
+    pb.finish_with_message("Coinbase maturity reached");
     
     // Generate UA fixtures
-    println!();
-    println!("Generating ZIP-316 Unified Address fixtures...");
+    say!();
+    say!("Generating ZIP-316 Unified Address fixtures...");
     
-    match generate_ua_fixtures(backend_uri).await {
+    match generate_ua_fixtures(&config.root, &wallet).await {
         Ok(address) => {
-            println!("Generated UA: {}...", &address[..20]);
+            say!("Generated UA: {}...", address.chars().take(20).collect::<String>());
+            report.addresses.wallet_unified = Some(address);
         }
         Err(e) => {
-            println!("{}", format!("Warning: Could not generate UA fixture ({})", e).yellow());
-            println!("  You can manually update fixtures/unified-addresses.json");
+            say!("{}", format!("Warning: Could not generate UA fixture ({})", e).yellow());
+            say!("  You can manually update fixtures/unified-addresses.json");
         }
     }
     
     // Sync wallet
-    println!();
-    println!("Syncing wallet with blockchain...");
-    if let Err(e) = sync_wallet(backend_uri).await {
-        println!("{}", format!("Wallet sync warning: {}", e).yellow());
+    say!();
+    say!("Syncing wallet with blockchain...");
+    if let Err(e) = wallet.sync().await {
+        say!("{}", format!("Wallet sync warning: {}", e).yellow());
     } else {
-        println!("Wallet synced with blockchain");
+        say!("Wallet synced with blockchain");
     }
-    
-    // Check balance
-    println!();
-    println!("Checking wallet balance...");
-    match check_wallet_balance().await {
-        Ok(balance) if balance > 0.0 => {
-            println!("Wallet has {} ZEC available", balance);
+    say!();
+    
+    // [7] Faucet wallet has scanned up to the tip
+    let pb = stage_counter(7, total_steps, "Syncing faucet wallet", 0);
+    wait_for_wallet_sync(&pb, &checker, &zebra, deadline.limit(timeouts.funds)).await?;
+    pb.finish_with_message("Faucet wallet synced");
+    
+    // [8] Spendable funds. Only a warning: the devnet is usable without
+    // them, and the faucet may not be able to report its balance
+    let pb = stage_spinner(8, total_steps, "Checking faucet balance");
+    match checker.faucet_stats().await {
+        Ok(stats) if stats.orchard_balance > 0.0 => {
+            pb.finish_with_message(format!("Faucet has {} ZEC spendable", stats.orchard_balance));
+            report.faucet_balance = Some(stats.orchard_balance);
         }
         Ok(_) => {
-            println!("{}", "Wallet synced but balance not yet available".yellow());
-            println!("  Blocks still maturing, wait a few more minutes");
+            pb.finish_with_message("Warning: Faucet wallet synced but has no spendable funds yet".yellow().to_string());
         }
         Err(e) => {
-            println!("{}", format!("Could not check balance: {}", e).yellow());
+            pb.finish_with_message(format!("Warning: Could not check faucet balance: {}", e).yellow().to_string());
         }
     }
     
-    print_connection_info(&backend);
-    print_mining_info().await?;
+    report.addresses.faucet = checker.faucet_stats().await.ok().and_then(|stats| stats.faucet_address);
     
-    Ok(())
+    print_connection_info(backend, &settings);
+    report.height = print_mining_info(&zebra).await;
+    
+    output::emit(&report)
 }
 
-async fn wait_for_wallet_ready(pb: &ProgressBar, backend_uri: &str) -> Result<()> {
-    let start = std::time::Instant::now();
+/// Wait until the zingo-wallet container's wallet answers with a
+/// transparent address.
+async fn wait_for_wallet_ready(pb: &ProgressBar, wallet: &ZingoCli, timeout: Duration) -> Result<()> {
+    let start = Instant::now();
     
     loop {
         pb.tick();
         
-        let cmd_str = format!(
-            "bash -c \"echo -e 't_addresses\\nquit' | zingo-cli --data-dir /var/zingo --server {} --chain regtest --nosync 2>&1\"",
-            backend_uri
-        );
+        let error = match wallet.transparent_address().await {
+            Ok(_) => return Ok(()),
+            Err(e) => e,
+        };
         
-        let output = Command::new("docker")
-            .args(&["exec", "zeckit-zingo-wallet", "bash", "-c", &cmd_str])
-            .output();
+        if start.elapsed() >= timeout {
+            return Err(ZeckitError::ServiceNotReady(format!(
+                "Wallet not ready after {}s: {}",
+                timeout.as_secs(),
+                error
+            )));
+        }
         
-        if let Ok(out) = output {
-            let output_str = String::from_utf8_lossy(&out.stdout);
-            if output_str.contains("tm") && output_str.contains("encoded_address") {
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+async fn wait_for_mined_blocks(
+    pb: &ProgressBar,
+    zebra: &ZebraRpcClient,
+    min_blocks: u64,
+    timeout: Duration,
+) -> Result<()> {
+    let start = Instant::now();
+    
+    loop {
+        if let Ok(height) = zebra.get_block_count().await {
+            pb.set_position(height.min(min_blocks));
+            if height >= min_blocks {
                 return Ok(());
             }
         }
         
-        if start.elapsed().as_secs() > WALLET_TIMEOUT_SECONDS {
-            return Err(zeckitError::ServiceNotReady("Wallet not ready after 100 minutes".into()));
+        if start.elapsed() >= timeout {
+            return Err(ZeckitError::ServiceNotReady(format!(
+                "Internal miner reached block {} of {} within {}s",
+                pb.position(),
+                min_blocks,
+                timeout.as_secs()
+            )));
         }
         
         sleep(Duration::from_secs(2)).await;
     }
 }
 
-async fn wait_for_mined_blocks(pb: &ProgressBar, min_blocks: u64) -> Result<()> {
-    let client = Client::new();
-    let start = std::time::Instant::now();
-    
-    println!("Mining blocks to maturity...");
+/// Wait until the faucet reports its wallet within its allowed lag of the
+/// chain tip, showing wallet height against the tip.
+async fn wait_for_wallet_sync(
+    pb: &ProgressBar,
+    checker: &HealthChecker,
+    zebra: &ZebraRpcClient,
+    timeout: Duration,
+) -> Result<()> {
+    let start = Instant::now();
     
     loop {
-        match get_block_count(&client).await {
-            Ok(height) if height >= min_blocks => {
-                println!("Mined {} blocks (coinbase maturity reached)", height);
-                println!();
+        if let (Ok(tip), Ok(stats)) = (zebra.get_block_count().await, checker.faucet_stats().await) {
+            let wallet_height = stats.wallet_height.unwrap_or(0);
+            pb.set_length(tip);
+            pb.set_position(wallet_height.min(tip));
+        }
+        
+        if let Ok(readiness) = checker.faucet_readiness().await {
+            if readiness.passes("wallet_sync") {
                 return Ok(());
             }
-            Ok(height) => {
-                let progress = (height as f64 / min_blocks as f64 * 100.0) as u64;
-                print!("\r  Block {} / {} ({}%)", height, min_blocks, progress);
-                io::stdout().flush().ok();
-            }
-            Err(_) => {}
         }
         
-        if start.elapsed().as_secs() > MAX_WAIT_SECONDS {
-            return Err(zeckitError::ServiceNotReady(
-                "Internal miner timeout - blocks not reaching maturity".into()
-            ));
+        if start.elapsed() >= timeout {
+            return Err(ZeckitError::ServiceNotReady(format!(
+                "Faucet wallet at block {} of {} after {}s",
+                pb.position(),
+                pb.length().unwrap_or(0),
+                timeout.as_secs()
+            )));
         }
         
         sleep(Duration::from_secs(2)).await;
     }
 }
 
-async fn get_block_count(client: &Client) -> Result<u64> {
-    let resp = client
-        .post("http://127.0.0.1:8232")
-        .json(&json!({
-            "jsonrpc": "2.0",
-            "id": "blockcount",
-            "method": "getblockcount",
-            "params": []
-        }))
-        .timeout(Duration::from_secs(5))
-        .send()
-        .await?;
-    
-    let json: serde_json::Value = resp.json().await?;
-    
-    json.get("result")
-        .and_then(|v| v.as_u64())
-        .ok_or_else(|| zeckitError::HealthCheck("Invalid block count response".into()))
+/// Write the wallet's unified address to `fixtures/unified-addresses.json`
+/// and return it.
+async fn generate_ua_fixtures(project_dir: &Path, wallet: &ZingoCli) -> Result<String> {
+    let address = wallet.address().await?;
+    let receivers = &address.receivers;
+    
+    let mut pools = Vec::new();
+    if receivers.orchard_exists {
+        pools.push("orchard");
+    }
+    if receivers.sapling.is_some() {
+        pools.push("sapling");
+    }
+    if receivers.transparent.is_some() {
+        pools.push("transparent");
+    }
+    
+    let fixture = json!({
+        "faucet_address": address.address,
+        "type": "unified",
+        "receivers": pools
+    });
+    
+    let fixtures_dir = project_dir.join("fixtures");
+    fs::create_dir_all(&fixtures_dir)?;
+    fs::write(
+        fixtures_dir.join("unified-addresses.json"),
+        serde_json::to_string_pretty(&fixture)?
+    )?;
+    
+    Ok(address.address)
 }
 
-async fn get_wallet_transparent_address(backend_uri: &str) -> Result<String> {
-    let cmd_str = format!(
-        "bash -c \"echo -e 't_addresses\\nquit' | zingo-cli --data-dir /var/zingo --server {} --chain regtest --nosync 2>&1\"",
-        backend_uri
-    );
+/// Where a failed build's output is saved, one file per attempt.
+fn build_log_path(config: &ProjectConfig) -> PathBuf {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    config.generated_dir().join("logs").join(format!("build-{}.log", secs))
+}
+
+/// Print and return the chain height, if Zebra answers.
+async fn print_mining_info(zebra: &ZebraRpcClient) -> Option<u64> {
+    let height = zebra.get_block_count().await.ok();
+    
+    if let Some(height) = height {
+        say!();
+        say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+        say!("{}", "  Blockchain Status".cyan().bold());
+        say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+        say!();
+        say!("  Block Height: {}", height);
+        say!("  Network: Regtest");
+        say!("  Mining: Active (internal miner)");
+        say!("  Pre-mined Funds: Available");
+    }
     
-    let output = Command::new("docker")
-        .args(&["exec", "zeckit-zingo-wallet", "bash", "-c", &cmd_str])
-        .output()
-        .map_err(|e| zeckitError::HealthCheck(format!("Docker exec failed: {}", e)))?;
-    
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    
-    for line in output_str.lines() {
-        if line.contains("\"encoded_address\"") && line.contains("tm") {
-            if let Some(start) = line.find("tm") {
-                let addr_part = &line[start..];
-                let end = addr_part.find(|c: char| c == '"' || c == '\n' || c == ' ')
-                    .unwrap_or(addr_part.len());
-                let address = &addr_part[..end];
-                
-                if address.starts_with("tm") && address.len() > 30 {
-                    return Ok(address.to_string());
-                }
-            }
+    height
+}
+
+/// The zingo-cli wallet can't be given activation heights and activates every
+/// upgrade at height 1, so it can't follow a chain with other heights.
+fn check_wallet_heights(backend: Backend, config: &ProjectConfig) -> Result<()> {
+    match &config.activation_heights {
+        Some(heights) if backend.has_wallet() && !heights.is_zingo_cli_default() => {
+            Err(ZeckitError::Config(format!(
+                "activation_heights ({}) need backend 'none': the zingo-cli wallet of backend '{}' only supports every upgrade at height 1",
+                heights.to_env(),
+                backend
+            )))
         }
+        _ => Ok(()),
     }
-    
-    Err(zeckitError::HealthCheck("Could not find transparent address in wallet output".into()))
 }
 
-fn update_zebra_miner_address(address: &str) -> Result<()> {
-    let zebra_config_path = "docker/configs/zebra.toml";
-    
-    let config = fs::read_to_string(zebra_config_path)
-        .map_err(|e| zeckitError::Config(format!("Could not read zebra.toml: {}", e)))?;
-    
-    let new_config = if config.contains("miner_address") {
-        use regex::Regex;
-        let re = Regex::new(r#"miner_address = "tm[a-zA-Z0-9]+""#).unwrap();
-        re.replace(&config, format!("miner_address = \"{}\"", address)).to_string()
-    } else {
-        config.replace(
-            "[mining]",
-            &format!("[mining]\nminer_address = \"{}\"", address)
-        )
+/// Check that Zebra runs with the activation heights from zeckit.toml, as
+/// the faucet and wallet are configured with the same heights.
+async fn verify_activation_heights(zebra: &ZebraRpcClient, config: &ProjectConfig) -> Result<()> {
+    let Some(heights) = &config.activation_heights else {
+        return Ok(());
     };
     
-    fs::write(zebra_config_path, new_config)
-        .map_err(|e| zeckitError::Config(format!("Could not write zebra.toml: {}", e)))?;
+    let info = zebra.get_blockchain_info().await?;
+    let mismatches = info.activation_height_mismatches(heights.entries());
+    
+    if !mismatches.is_empty() {
+        return Err(ZeckitError::Config(format!(
+            "Zebra's activation heights don't match zeckit.toml: {}",
+            mismatches.join("; ")
+        )));
+    }
     
+    say!("Activation heights verified: {}", heights.to_env());
     Ok(())
 }
 
-async fn restart_zebra() -> Result<()> {
-    let output = Command::new("docker")
-        .args(&["restart", "zeckit-zebra"])
-        .output()
-        .map_err(|e| zeckitError::Docker(format!("Failed to restart Zebra: {}", e)))?;
-    
-    if !output.status.success() {
-        return Err(zeckitError::Docker("Zebra restart failed".into()));
+/// Fail fast when the running containers don't match the requested backend,
+/// e.g. when a service of the other indexer is left over.
+fn verify_topology(compose: &DockerCompose, backend: Backend, expected: &[&str]) -> Result<()> {
+    let running = compose.running_services()?;
+    
+    let missing: Vec<&str> = expected
+        .iter()
+        .copied()
+        .filter(|service| !running.iter().any(|r| r == service))
+        .collect();
+    
+    let unexpected: Vec<&str> = Backend::INDEXERS
+        .iter()
+        .flat_map(|other| other.services())
+        .filter(|service| !expected.contains(service))
+        .filter(|service| running.iter().any(|r| r == service))
+        .collect();
+    
+    if !missing.is_empty() || !unexpected.is_empty() {
+        return Err(ZeckitError::Docker(format!(
+            "Running services don't match backend '{}' (missing: [{}], unexpected: [{}])",
+            backend,
+            missing.join(", "),
+            unexpected.join(", ")
+        )));
     }
     
-    sleep(Duration::from_secs(15)).await;
-    
     Ok(())
 }
 
-async fn generate_ua_fixtures(backend_uri: &str) -> Result<String> {
-    let cmd_str = format!(
-        "bash -c \"echo -e 'addresses\\nquit' | zingo-cli --data-dir /var/zingo --server {} --chain regtest --nosync 2>&1\"",
-        backend_uri
-    );
-    
-    let output = Command::new("docker")
-        .args(&["exec", "zeckit-zingo-wallet", "bash", "-c", &cmd_str])
-        .output()
-        .map_err(|e| zeckitError::HealthCheck(format!("Docker exec failed: {}", e)))?;
-    
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    
-    for line in output_str.lines() {
-        if line.contains("uregtest") {
-            if let Some(start) = line.find("uregtest") {
-                let addr_part = &line[start..];
-                let end = addr_part.find(|c: char| c == '"' || c == '\n' || c == ' ')
-                    .unwrap_or(addr_part.len());
-                let address = &addr_part[..end];
-                
-                let fixture = json!({
-                    "faucet_address": address,
-                    "type": "unified",
-                    "receivers": ["orchard"]
-                });
-                
-                fs::create_dir_all("fixtures")?;
-                fs::write(
-                    "fixtures/unified-addresses.json",
-                    serde_json::to_string_pretty(&fixture)?
-                )?;
-                
-                return Ok(address.to_string());
-            }
-        }
+pub fn print_connection_info(backend: Backend, settings: &Settings) {
+    say!();
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!("{}", "  Services Ready".cyan().bold());
+    say!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
+    say!();
+    say!("  Zebra RPC: {}", settings.zebra_rpc_url);
+    
+    if backend.has_wallet() {
+        say!("  Faucet API: {}", settings.faucet_api_url);
+        say!("  {}: {}", backend.display_name(), settings.backend_url);
     }
     
-    Err(zeckitError::HealthCheck("Could not find wallet address in output".into()))
+    say!();
+    say!("Next steps:");
+    say!("  • Run tests: zeckit test");
+    say!("  • View fixtures: cat fixtures/unified-addresses.json");
+    say!();
 }
 
-async fn sync_wallet(backend_uri: &str) -> Result<()> {
-    let cmd_str = format!(
-        "echo 'sync run\nquit' | zingo-cli --data-dir /var/zingo --server {} --chain regtest 2>&1",
-        backend_uri
-    );
-    
-    let output = Command::new("docker")
-        .args(&[
-            "exec", "-i", "zeckit-zingo-wallet",
-            "sh", "-c",
-            &cmd_str
-        ])
-        .output()
-        .map_err(|e| zeckitError::HealthCheck(format!("Sync command failed: {}", e)))?;
-    
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    
-    if output_str.contains("Sync error") {
-        Err(zeckitError::HealthCheck("Wallet sync error detected".into()))
-    } else {
-        Ok(())
-    }
+/// `[n/total]` prefix for readiness stage `step`.
+fn stage_prefix(step: usize, total: usize) -> String {
+    format!("[{}/{}]", step, total)
 }
 
-async fn check_wallet_balance() -> Result<f64> {
-    let client = Client::new();
-    let resp = client
-        .get("http://127.0.0.1:8080/stats")
-        .timeout(Duration::from_secs(5))
-        .send()
-        .await?;
-    
-    let json: serde_json::Value = resp.json().await?;
-    Ok(json["current_balance"].as_f64().unwrap_or(0.0))
+/// Spinner for a stage whose only signal is whether it has passed.
+pub fn stage_spinner(step: usize, total: usize, message: &str) -> ProgressBar {
+    let pb = output::progress(ProgressBar::new_spinner());
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("{prefix} {spinner:.green} {msg} ({elapsed})")
+            .unwrap()
+    );
+    pb.set_prefix(stage_prefix(step, total));
+    pb.set_message(message.to_string());
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb
 }
 
-async fn print_mining_info() -> Result<()> {
-    let client = Client::new();
-    
-    if let Ok(height) = get_block_count(&client).await {
-        println!();
-        println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-        println!("{}", "  Blockchain Status".cyan().bold());
-        println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-        println!();
-        println!("  Block Height: {}", height);
-        println!("  Network: Regtest");
-        println!("  Mining: Active (internal miner)");
-        println!("  Pre-mined Funds: Available");
-    }
-    
-    Ok(())
+/// Bar for a stage measured in blocks.
+fn stage_counter(step: usize, total: usize, message: &str, blocks: u64) -> ProgressBar {
+    let pb = output::progress(ProgressBar::new(blocks));
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{prefix} {msg} [{bar:30.green/white}] block {pos}/{len} ({elapsed})")
+            .unwrap()
+            .progress_chars("=> ")
+    );
+    pb.set_prefix(stage_prefix(step, total));
+    pb.set_message(message.to_string());
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb
 }
-
-fn print_connection_info(backend: &str) {
-    println!();
-    println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-    println!("{}", "  Services Ready".cyan().bold());
-    println!("{}", "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".cyan());
-    println!();
-    println!("  Zebra RPC: http://127.0.0.1:8232");
-    println!("  Faucet API: http://127.0.0.1:8080");
-    
-    if backend == "lwd" {
-        println!("  LightwalletD: http://127.0.0.1:9067");
-    } else if backend == "zaino" {
-        println!("  Zaino: http://127.0.0.1:9067");
-    }
-    
-    println!();
-    println!("Next steps:");
-    println!("  • Run tests: zeckit test");
-    println!("  • View fixtures: cat fixtures/unified-addresses.json");
-    println!();
-}
\ No newline at end of file
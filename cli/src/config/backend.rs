@@ -0,0 +1,93 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Light-client backend (indexer) the devnet runs behind the wallet and faucet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// lightwalletd
+    #[value(alias = "lightwalletd")]
+    #[serde(alias = "lightwalletd")]
+    Lwd,
+    /// Zaino
+    Zaino,
+    /// Zebra only, no indexer, wallet or faucet
+    None,
+}
+
+impl Backend {
+    /// Backends that run an indexer, i.e. have a compose profile.
+    pub const INDEXERS: [Backend; 2] = [Backend::Lwd, Backend::Zaino];
+
+    /// Compose profile enabling this backend's services.
+    pub fn profile(&self) -> Option<&'static str> {
+        match self {
+            Backend::Lwd => Some("lwd"),
+            Backend::Zaino => Some("zaino"),
+            Backend::None => None,
+        }
+    }
+
+    /// Compose service running the indexer itself.
+    pub fn indexer_service(&self) -> Option<&'static str> {
+        match self {
+            Backend::Lwd => Some("lightwalletd"),
+            Backend::Zaino => Some("zaino"),
+            Backend::None => None,
+        }
+    }
+
+    /// Every compose service that makes up this topology.
+    pub fn services(&self) -> Vec<&'static str> {
+        match self {
+            Backend::Lwd => vec!["zebra", "lightwalletd", "zingo-wallet-lwd", "faucet-lwd"],
+            Backend::Zaino => vec!["zebra", "zaino", "zingo-wallet-zaino", "faucet-zaino"],
+            Backend::None => vec!["zebra"],
+        }
+    }
+
+    /// gRPC URI of the indexer inside the compose network, as used by the
+    /// wallet and the faucet.
+    pub fn uri(&self) -> Option<&'static str> {
+        match self {
+            Backend::Lwd => Some("http://lightwalletd:9067"),
+            Backend::Zaino => Some("http://zaino:9067"),
+            Backend::None => None,
+        }
+    }
+
+    /// Compose variable overriding the indexer's host port, so that both
+    /// indexers can run at once.
+    pub fn port_env(&self) -> Option<&'static str> {
+        match self {
+            Backend::Lwd => Some("ZECKIT_LWD_PORT"),
+            Backend::Zaino => Some("ZECKIT_ZAINO_PORT"),
+            Backend::None => None,
+        }
+    }
+
+    /// Whether this topology includes the zingo wallet and the faucet.
+    pub fn has_wallet(&self) -> bool {
+        self.uri().is_some()
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Backend::Lwd => "Lightwalletd",
+            Backend::Zaino => "Zaino",
+            Backend::None => "None",
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Backend::Lwd => "lwd",
+            Backend::Zaino => "zaino",
+            Backend::None => "none",
+        };
+        f.write_str(name)
+    }
+}
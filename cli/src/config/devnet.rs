@@ -0,0 +1,175 @@
+use crate::config::backend::Backend;
+use crate::config::project::{PortsConfig, ProjectConfig, GENERATED_DIR};
+use crate::error::{Result, ZeckitError};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+
+/// A named devnet started with `zeckit up --name`. Recorded under
+/// `.zeckit/devnets/` so that `status`, `down` and `test` can find its
+/// compose project, containers and host ports.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DevnetState {
+    pub name: String,
+    pub compose_project: String,
+    pub container_prefix: String,
+    pub backend: Backend,
+    pub ports: PortsConfig,
+}
+
+impl DevnetState {
+    /// New devnet state with freshly allocated host ports.
+    pub fn new(name: &str, backend: Backend) -> Result<Self> {
+        validate_name(name)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            compose_project: format!("zeckit-{}", name),
+            container_prefix: format!("zeckit-{}", name),
+            backend,
+            ports: allocate_ports()?,
+        })
+    }
+
+    fn path(root: &Path, name: &str) -> PathBuf {
+        root.join(GENERATED_DIR)
+            .join("devnets")
+            .join(format!("{}.json", name))
+    }
+
+    /// State of devnet `name`, if it has been started.
+    pub fn load(root: &Path, name: &str) -> Result<Option<Self>> {
+        validate_name(name)?;
+
+        let path = Self::path(root, name);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        Ok(Some(serde_json::from_str(&contents)?))
+    }
+
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::path(root, &self.name);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn remove(root: &Path, name: &str) -> Result<()> {
+        let path = Self::path(root, name);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Point `config` at this devnet's compose project, containers and ports.
+    pub fn apply(&self, config: &mut ProjectConfig) {
+        config.devnet_name = Some(self.name.clone());
+        config.devnet.container_prefix = self.container_prefix.clone();
+        config.ports = self.ports.clone();
+    }
+}
+
+/// Devnet names become part of compose project and container names, so
+/// they are limited to lowercase letters, digits, `-` and `_`.
+fn validate_name(name: &str) -> Result<()> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+
+    if !valid {
+        return Err(ZeckitError::Config(format!(
+            "Invalid devnet name '{}': use lowercase letters, digits, '-' and '_'",
+            name
+        )));
+    }
+
+    Ok(())
+}
+
+/// Pick four distinct free ports on 127.0.0.1. The listeners are held until
+/// all ports are chosen so the OS doesn't hand out the same port twice.
+fn allocate_ports() -> Result<PortsConfig> {
+    let listeners = (0..4)
+        .map(|_| TcpListener::bind(("127.0.0.1", 0)))
+        .collect::<std::io::Result<Vec<_>>>()?;
+
+    let ports = listeners
+        .iter()
+        .map(|listener| listener.local_addr().map(|addr| addr.port()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+
+    Ok(PortsConfig {
+        zebra_rpc: ports[0],
+        zebra_p2p: ports[1],
+        indexer_grpc: ports[2],
+        faucet: ports[3],
+    })
+}
+
+/// Load the project config, pointed at devnet `name` when given. Named
+/// devnets must have been started with `zeckit up --name`.
+pub fn load_project(name: Option<&str>) -> Result<ProjectConfig> {
+    let mut config = ProjectConfig::discover()?;
+
+    if let Some(name) = name {
+        let state = DevnetState::load(&config.root, name)?.ok_or_else(|| {
+            ZeckitError::Config(format!(
+                "No devnet named '{}' (start it with `zeckit up --name {}`)",
+                name, name
+            ))
+        })?;
+        state.apply(&mut config);
+    }
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        for name in ["ci", "pr-42", "branch_a", "7"] {
+            assert!(validate_name(name).is_ok(), "rejected: {}", name);
+        }
+        for name in ["", "CI", "-ci", "a/b", "a b", "../x"] {
+            assert!(validate_name(name).is_err(), "accepted: {}", name);
+        }
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = DevnetState::new("ci", Backend::Zaino).unwrap();
+
+        let ports = &state.ports;
+        let mut distinct = [ports.zebra_rpc, ports.zebra_p2p, ports.indexer_grpc, ports.faucet];
+        distinct.sort_unstable();
+        assert!(distinct.windows(2).all(|pair| pair[0] != pair[1]));
+
+        assert_eq!(DevnetState::load(dir.path(), "ci").unwrap(), None);
+        state.save(dir.path()).unwrap();
+        assert_eq!(DevnetState::load(dir.path(), "ci").unwrap(), Some(state.clone()));
+
+        let mut config = ProjectConfig::default();
+        state.apply(&mut config);
+        assert_eq!(config.container("zebra"), "zeckit-ci-zebra");
+        assert_eq!(config.compose_project().as_deref(), Some("zeckit-ci"));
+        assert_eq!(config.ports.faucet, state.ports.faucet);
+
+        DevnetState::remove(dir.path(), "ci").unwrap();
+        assert_eq!(DevnetState::load(dir.path(), "ci").unwrap(), None);
+    }
+}
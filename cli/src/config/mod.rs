@@ -1 +1,5 @@
-pub mod settings;
\ No newline at end of file
+pub mod backend;
+pub mod devnet;
+pub mod project;
+pub mod settings;
+pub mod zebra;
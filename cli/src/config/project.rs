@@ -0,0 +1,523 @@
+use crate::config::backend::Backend;
+use crate::config::zebra::{ActivationHeights, ZebraConfig};
+use crate::error::{Result, ZeckitError};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Project file declaring the devnet topology.
+pub const CONFIG_FILE: &str = "zeckit.toml";
+
+/// Directory, relative to the project root, holding files rendered from
+/// `zeckit.toml`. Never edited by hand.
+pub const GENERATED_DIR: &str = ".zeckit";
+
+/// Compose file rendered from the `[services.*]` tables.
+const SERVICES_COMPOSE_FILE: &str = "docker-compose.services.yml";
+
+/// Services defined by the bundled compose file; extra services may not reuse these names.
+const BUILTIN_SERVICES: [&str; 7] = [
+    "zebra",
+    "lightwalletd",
+    "zaino",
+    "zingo-wallet-lwd",
+    "zingo-wallet-zaino",
+    "faucet-lwd",
+    "faucet-zaino",
+];
+
+/// Contents of `zeckit.toml`. Every section is optional and falls back to
+/// the defaults the bundled compose file was written for.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub devnet: DevnetConfig,
+    pub ports: PortsConfig,
+    pub faucet: FaucetConfig,
+    pub timeouts: TimeoutsConfig,
+    /// Regtest network upgrade heights, applied to Zebra, the faucet and the
+    /// wallet. Zebra's defaults are used when unset.
+    pub activation_heights: Option<ActivationHeights>,
+    /// Additional compose services started alongside the devnet.
+    pub services: BTreeMap<String, ExtraService>,
+
+    /// Directory containing `zeckit.toml` and `docker-compose.yml`.
+    #[serde(skip)]
+    pub root: PathBuf,
+
+    /// Name of the devnet selected with `--name`, if any.
+    #[serde(skip)]
+    pub devnet_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DevnetConfig {
+    /// Backend used by `zeckit up` when `--backend` is not given.
+    pub backend: Backend,
+    /// Blocks mined before the devnet is reported ready (coinbase maturity is 100).
+    pub premine_blocks: u64,
+    /// Prefix of every container name, e.g. `zeckit` gives `zeckit-zebra`.
+    pub container_prefix: String,
+    /// Template for the Zebra config, relative to the project root. `zeckit up`
+    /// renders the config Zebra actually uses into the generated directory.
+    pub zebra_config: PathBuf,
+}
+
+impl Default for DevnetConfig {
+    fn default() -> Self {
+        Self {
+            backend: Backend::None,
+            premine_blocks: 101,
+            container_prefix: "zeckit".to_string(),
+            zebra_config: PathBuf::from("docker/configs/zebra.toml"),
+        }
+    }
+}
+
+/// Host ports, all bound to 127.0.0.1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PortsConfig {
+    pub zebra_rpc: u16,
+    pub zebra_p2p: u16,
+    pub indexer_grpc: u16,
+    pub faucet: u16,
+}
+
+impl Default for PortsConfig {
+    fn default() -> Self {
+        Self {
+            zebra_rpc: 8232,
+            zebra_p2p: 8233,
+            indexer_grpc: 9067,
+            faucet: 8080,
+        }
+    }
+}
+
+/// Payout limits passed to the faucet, in ZEC.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FaucetConfig {
+    pub amount_min: f64,
+    pub amount_max: f64,
+    pub amount_default: f64,
+}
+
+impl Default for FaucetConfig {
+    fn default() -> Self {
+        Self {
+            amount_min: 0.01,
+            amount_max: 100.0,
+            amount_default: 10.0,
+        }
+    }
+}
+
+/// How long `zeckit up` waits for each stage, in seconds. No stage waits
+/// past `total`, counted from when the services were started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimeoutsConfig {
+    pub zebra: u64,
+    pub indexer: u64,
+    pub wallet: u64,
+    pub faucet: u64,
+    pub mining: u64,
+    /// Faucet wallet sync to the chain tip.
+    pub funds: u64,
+    pub total: u64,
+}
+
+impl Default for TimeoutsConfig {
+    fn default() -> Self {
+        Self {
+            zebra: 120,
+            indexer: 180,
+            wallet: 300,
+            faucet: 60,
+            mining: 1200,
+            funds: 600,
+            total: 1800,
+        }
+    }
+}
+
+/// An extra compose service, rendered into the generated compose file and
+/// attached to the devnet network.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExtraService {
+    pub image: Option<String>,
+    /// Build context, relative to the project root.
+    pub build: Option<String>,
+    pub command: Option<Vec<String>>,
+    pub ports: Vec<String>,
+    pub environment: BTreeMap<String, String>,
+    pub volumes: Vec<String>,
+    pub depends_on: Vec<String>,
+}
+
+/// Nearest directory at or above `start` containing `zeckit.toml`.
+pub fn find_project_root(start: &Path) -> Result<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(CONFIG_FILE).is_file())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| {
+            ZeckitError::Config(format!(
+                "No {} found in {} or any parent directory (run `zeckit init` to create one)",
+                CONFIG_FILE,
+                start.display()
+            ))
+        })
+}
+
+impl ProjectConfig {
+    /// Load the configuration of the project containing the current directory.
+    pub fn discover() -> Result<Self> {
+        Self::load(&find_project_root(&std::env::current_dir()?)?)
+    }
+
+    /// Load `zeckit.toml` from `root`, using the defaults when it doesn't exist.
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(CONFIG_FILE);
+
+        let mut config = if path.exists() {
+            let contents = fs::read_to_string(&path)?;
+            Self::parse(&contents)
+                .map_err(|e| ZeckitError::Config(format!("{}: {}", path.display(), e)))?
+        } else {
+            Self::default()
+        };
+
+        config.root = root.to_path_buf();
+        Ok(config)
+    }
+
+    /// Parse and validate `zeckit.toml` contents.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let config: Self =
+            toml::from_str(contents).map_err(|e| ZeckitError::Config(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let faucet = &self.faucet;
+        if !(faucet.amount_min > 0.0
+            && faucet.amount_min <= faucet.amount_default
+            && faucet.amount_default <= faucet.amount_max)
+        {
+            return Err(ZeckitError::Config(
+                "faucet amounts must satisfy 0 < amount_min <= amount_default <= amount_max".into(),
+            ));
+        }
+
+        if let Some(heights) = &self.activation_heights {
+            heights.validate()?;
+        }
+
+        if self.devnet.premine_blocks == 0 {
+            return Err(ZeckitError::Config("devnet.premine_blocks must be at least 1".into()));
+        }
+
+        let ports = &self.ports;
+        let mut host_ports = [ports.zebra_rpc, ports.zebra_p2p, ports.indexer_grpc, ports.faucet];
+        host_ports.sort_unstable();
+        if host_ports.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(ZeckitError::Config("ports must be distinct".into()));
+        }
+
+        for (name, service) in &self.services {
+            if BUILTIN_SERVICES.contains(&name.as_str()) {
+                return Err(ZeckitError::Config(format!(
+                    "services.{}: name clashes with a built-in service",
+                    name
+                )));
+            }
+            if service.image.is_none() && service.build.is_none() {
+                return Err(ZeckitError::Config(format!(
+                    "services.{}: either image or build is required",
+                    name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Name of the container running `service`, e.g. `zebra` or `zingo-wallet`.
+    pub fn container(&self, service: &str) -> String {
+        format!("{}-{}", self.devnet.container_prefix, service)
+    }
+
+    /// Compose project name of a named devnet. The default devnet uses
+    /// compose's own default.
+    pub fn compose_project(&self) -> Option<String> {
+        self.devnet_name.as_ref().map(|name| format!("zeckit-{}", name))
+    }
+
+    pub fn zebra_template_path(&self) -> PathBuf {
+        self.root.join(&self.devnet.zebra_config)
+    }
+
+    /// Directory for files generated for the selected devnet.
+    pub fn generated_dir(&self) -> PathBuf {
+        let dir = self.root.join(GENERATED_DIR);
+        match &self.devnet_name {
+            // Container names and miner addresses differ per devnet, so each gets its own copy
+            Some(name) => dir.join("devnets").join(name),
+            None => dir,
+        }
+    }
+
+    /// Zebra config rendered from the template and mounted into the zebra container.
+    pub fn zebra_config_path(&self) -> PathBuf {
+        self.generated_dir().join("zebra.toml")
+    }
+
+    /// Render the Zebra config from the template, mining to `miner_address`.
+    /// Without an address, the one from a previous render is kept, falling
+    /// back to the template's.
+    pub fn render_zebra_config(&self, miner_address: Option<&str>) -> Result<()> {
+        let mut zebra = ZebraConfig::load(&self.zebra_template_path())?;
+        let path = self.zebra_config_path();
+
+        let previous = if path.exists() {
+            ZebraConfig::load(&path)?.mining.miner_address
+        } else {
+            None
+        };
+
+        if let Some(address) = miner_address.map(str::to_string).or(previous) {
+            zebra.mining.miner_address = Some(address);
+        }
+
+        if let Some(heights) = &self.activation_heights {
+            zebra.set_activation_heights(heights.clone());
+        }
+
+        zebra.write(&path)
+    }
+
+    /// Names of the extra services declared in `zeckit.toml`.
+    pub fn extra_services(&self) -> Vec<&str> {
+        self.services.keys().map(String::as_str).collect()
+    }
+
+    /// Variables interpolated by `docker-compose.yml`.
+    pub fn compose_env(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("ZECKIT_CONTAINER_PREFIX", self.devnet.container_prefix.clone()),
+            ("ZECKIT_ZEBRA_CONFIG", self.zebra_config_path().to_string_lossy().to_string()),
+            ("ZECKIT_ZEBRA_RPC_PORT", self.ports.zebra_rpc.to_string()),
+            ("ZECKIT_ZEBRA_P2P_PORT", self.ports.zebra_p2p.to_string()),
+            ("ZECKIT_INDEXER_PORT", self.ports.indexer_grpc.to_string()),
+            ("ZECKIT_FAUCET_PORT", self.ports.faucet.to_string()),
+            ("ZECKIT_FAUCET_AMOUNT_MIN", self.faucet.amount_min.to_string()),
+            ("ZECKIT_FAUCET_AMOUNT_MAX", self.faucet.amount_max.to_string()),
+            ("ZECKIT_FAUCET_AMOUNT_DEFAULT", self.faucet.amount_default.to_string()),
+            (
+                "ZECKIT_ACTIVATION_HEIGHTS",
+                self.activation_heights
+                    .as_ref()
+                    .map(ActivationHeights::to_env)
+                    .unwrap_or_default(),
+            ),
+        ]
+    }
+
+    /// Path of the compose file rendered from the `[services.*]` tables.
+    pub fn services_compose_path(&self) -> PathBuf {
+        self.generated_dir().join(SERVICES_COMPOSE_FILE)
+    }
+
+    /// Write the compose file for the extra services, or remove a stale one
+    /// when none are declared.
+    pub fn render_services_compose(&self) -> Result<()> {
+        let path = self.services_compose_path();
+
+        if self.services.is_empty() {
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            return Ok(());
+        }
+
+        let services: BTreeMap<&str, serde_json::Value> = self
+            .services
+            .iter()
+            .map(|(name, service)| (name.as_str(), self.render_service(name, service)))
+            .collect();
+
+        let compose = json!({ "services": services });
+        let yaml = serde_yaml::to_string(&compose)
+            .map_err(|e| ZeckitError::Config(format!("Could not render services: {}", e)))?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(
+            &path,
+            format!("# Generated by zeckit from {}. Do not edit.\n{}", CONFIG_FILE, yaml),
+        )?;
+
+        Ok(())
+    }
+
+    fn render_service(&self, name: &str, service: &ExtraService) -> serde_json::Value {
+        let mut rendered = json!({
+            "container_name": self.container(name),
+            "networks": ["zeckit-network"],
+            "restart": "unless-stopped",
+        });
+
+        if let Some(image) = &service.image {
+            rendered["image"] = json!(image);
+        }
+        if let Some(build) = &service.build {
+            // Relative paths in an extra compose file resolve against the
+            // first file's directory, but be explicit
+            rendered["build"] = json!(self.root.join(build).to_string_lossy());
+        }
+        if let Some(command) = &service.command {
+            rendered["command"] = json!(command);
+        }
+        if !service.ports.is_empty() {
+            rendered["ports"] = json!(service.ports);
+        }
+        if !service.environment.is_empty() {
+            rendered["environment"] = json!(service.environment);
+        }
+        if !service.volumes.is_empty() {
+            rendered["volumes"] = json!(service.volumes);
+        }
+        if !service.depends_on.is_empty() {
+            rendered["depends_on"] = json!(service.depends_on);
+        }
+
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_file_uses_defaults() {
+        let config = ProjectConfig::parse("").unwrap();
+        assert_eq!(config.devnet.backend, Backend::None);
+        assert_eq!(config.devnet.premine_blocks, 101);
+        assert_eq!(config.ports.zebra_rpc, 8232);
+        assert_eq!(config.container("zebra"), "zeckit-zebra");
+        assert!(config.services.is_empty());
+    }
+
+    #[test]
+    fn test_example_file_parses() {
+        let config = ProjectConfig::parse(include_str!("../../../zeckit.toml")).unwrap();
+        let defaults = ProjectConfig::default();
+        assert_eq!(config.compose_env(), defaults.compose_env());
+        assert_eq!(config.devnet.premine_blocks, defaults.devnet.premine_blocks);
+    }
+
+    #[test]
+    fn test_find_project_root_walks_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("src").join("deep");
+        fs::create_dir_all(&nested).unwrap();
+
+        assert!(matches!(find_project_root(&nested), Err(ZeckitError::Config(_))));
+
+        fs::write(dir.path().join(CONFIG_FILE), "").unwrap();
+        assert_eq!(find_project_root(&nested).unwrap(), dir.path());
+        assert_eq!(find_project_root(dir.path()).unwrap(), dir.path());
+    }
+
+    #[test]
+    fn test_full_file() {
+        let config = ProjectConfig::parse(
+            r#"
+            [devnet]
+            backend = "zaino"
+            premine_blocks = 200
+
+            [ports]
+            zebra_rpc = 18232
+            faucet = 18080
+
+            [activation_heights]
+            Overwinter = 1
+            Sapling = 1
+            Blossom = 1
+            Heartwood = 1
+            Canopy = 1
+            NU5 = 1
+            NU6 = 1
+
+            [faucet]
+            amount_max = 5.0
+            amount_default = 1.0
+
+            [services.explorer]
+            image = "example/explorer:latest"
+            ports = ["127.0.0.1:3000:3000"]
+            environment = { ZEBRA_RPC = "http://zebra:8232" }
+            depends_on = ["zebra"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.devnet.backend, Backend::Zaino);
+        assert_eq!(config.devnet.premine_blocks, 200);
+        assert_eq!(config.ports.zebra_rpc, 18232);
+        assert_eq!(config.ports.indexer_grpc, 9067);
+        assert_eq!(config.faucet.amount_max, 5.0);
+        assert_eq!(config.extra_services(), vec!["explorer"]);
+        assert!(config.activation_heights.unwrap().is_zingo_cli_default());
+    }
+
+    #[test]
+    fn test_invalid_configs_are_rejected() {
+        for contents in [
+            "[devnet]\nunknown = 1",
+            "[faucet]\namount_min = 10.0\namount_default = 1.0",
+            "[ports]\nzebra_rpc = 8080",
+            "[services.zebra]\nimage = \"zfnd/zebra\"",
+            "[services.explorer]\nports = [\"3000:3000\"]",
+            "[activation_heights]\nNU5 = 10\nNU6 = 5",
+            "[activation_heights]\nNU5 = 1",
+            "[activation_heights]\nNU7 = 10",
+        ] {
+            assert!(
+                matches!(ProjectConfig::parse(contents), Err(ZeckitError::Config(_))),
+                "accepted: {}",
+                contents
+            );
+        }
+    }
+
+    #[test]
+    fn test_render_services_compose() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = ProjectConfig::parse(
+            "[services.explorer]\nimage = \"example/explorer\"\ndepends_on = [\"zebra\"]",
+        )
+        .unwrap();
+        config.root = dir.path().to_path_buf();
+
+        config.render_services_compose().unwrap();
+        let rendered = fs::read_to_string(config.services_compose_path()).unwrap();
+        assert!(rendered.contains("container_name: zeckit-explorer"));
+        assert!(rendered.contains("image: example/explorer"));
+
+        config.services.clear();
+        config.render_services_compose().unwrap();
+        assert!(!config.services_compose_path().exists());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::config::project::ProjectConfig;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,7 +19,12 @@ impl Default for Settings {
 }
 
 impl Settings {
-    pub fn new() -> Self {
-        Self::default()
+    /// Host-side URLs of the services, using the ports from `zeckit.toml`.
+    pub fn from_config(config: &ProjectConfig) -> Self {
+        Self {
+            zebra_rpc_url: format!("http://127.0.0.1:{}", config.ports.zebra_rpc),
+            faucet_api_url: format!("http://127.0.0.1:{}", config.ports.faucet),
+            backend_url: format!("http://127.0.0.1:{}", config.ports.indexer_grpc),
+        }
     }
 }
\ No newline at end of file
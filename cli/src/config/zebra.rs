@@ -0,0 +1,302 @@
+use crate::error::{Result, ZeckitError};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Zebra's `zebrad.toml`. The sections ZecKit sets are typed; anything else
+/// in the template is carried through unchanged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZebraConfig {
+    pub network: NetworkSection,
+    pub rpc: RpcSection,
+    pub mining: MiningSection,
+    #[serde(flatten)]
+    pub other: toml::Table,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSection {
+    pub network: String,
+    pub listen_addr: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub testnet_parameters: Option<TestnetParameters>,
+    #[serde(flatten)]
+    pub other: toml::Table,
+}
+
+impl Default for NetworkSection {
+    fn default() -> Self {
+        Self {
+            network: "Regtest".to_string(),
+            listen_addr: "0.0.0.0:8233".to_string(),
+            testnet_parameters: None,
+            other: toml::Table::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TestnetParameters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activation_heights: Option<ActivationHeights>,
+    #[serde(flatten)]
+    pub other: toml::Table,
+}
+
+/// Network upgrade activation heights, as accepted by Zebra for Regtest and
+/// named as in `getblockchaininfo`. Optional so that Zebra config templates
+/// with partial heights still parse; `zeckit.toml` must set every upgrade.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", deny_unknown_fields)]
+pub struct ActivationHeights {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overwinter: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sapling: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blossom: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heartwood: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canopy: Option<u32>,
+    #[serde(rename = "NU5", skip_serializing_if = "Option::is_none")]
+    pub nu5: Option<u32>,
+    #[serde(rename = "NU6", skip_serializing_if = "Option::is_none")]
+    pub nu6: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RpcSection {
+    pub listen_addr: String,
+    pub enable_cookie_auth: bool,
+    #[serde(flatten)]
+    pub other: toml::Table,
+}
+
+impl Default for RpcSection {
+    fn default() -> Self {
+        Self {
+            listen_addr: "0.0.0.0:8232".to_string(),
+            enable_cookie_auth: false,
+            other: toml::Table::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MiningSection {
+    pub internal_miner: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub miner_address: Option<String>,
+    #[serde(flatten)]
+    pub other: toml::Table,
+}
+
+impl Default for MiningSection {
+    fn default() -> Self {
+        Self {
+            internal_miner: true,
+            miner_address: None,
+            other: toml::Table::new(),
+        }
+    }
+}
+
+/// Upgrades `[activation_heights]` sets, in activation order.
+const UPGRADES: [&str; 7] = ["Overwinter", "Sapling", "Blossom", "Heartwood", "Canopy", "NU5", "NU6"];
+
+impl ActivationHeights {
+    /// Configured heights in activation order.
+    pub fn entries(&self) -> Vec<(&'static str, u32)> {
+        [
+            ("Overwinter", self.overwinter),
+            ("Sapling", self.sapling),
+            ("Blossom", self.blossom),
+            ("Heartwood", self.heartwood),
+            ("Canopy", self.canopy),
+            ("NU5", self.nu5),
+            ("NU6", self.nu6),
+        ]
+        .into_iter()
+        .filter_map(|(name, height)| height.map(|height| (name, height)))
+        .collect()
+    }
+
+    /// Every upgrade must be set, so that Zebra, the faucet and the wallet
+    /// can't fall back to different defaults. Upgrades activate in order, so
+    /// heights may not decrease.
+    pub fn validate(&self) -> Result<()> {
+        let entries = self.entries();
+        let missing: Vec<&str> = UPGRADES
+            .into_iter()
+            .filter(|upgrade| !entries.iter().any(|(name, _)| name == upgrade))
+            .collect();
+        if !missing.is_empty() {
+            return Err(ZeckitError::Config(format!(
+                "activation_heights: set every upgrade (missing {})",
+                missing.join(", ")
+            )));
+        }
+
+        for pair in entries.windows(2) {
+            let ((earlier, earlier_height), (later, later_height)) = (pair[0], pair[1]);
+            if later_height < earlier_height {
+                return Err(ZeckitError::Config(format!(
+                    "activation_heights: {} ({}) cannot activate before {} ({})",
+                    later, later_height, earlier, earlier_height
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether these are the heights zingo-cli uses on regtest: every upgrade
+    /// at height 1. zingo-cli has no setting to change them.
+    pub fn is_zingo_cli_default(&self) -> bool {
+        self.entries().iter().all(|(_, height)| *height == 1)
+    }
+
+    /// `Name=height` pairs, comma separated, as read by the faucet and wallet
+    /// containers from `ACTIVATION_HEIGHTS`.
+    pub fn to_env(&self) -> String {
+        self.entries()
+            .iter()
+            .map(|(name, height)| format!("{}={}", name, height))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl ZebraConfig {
+    pub fn parse(contents: &str) -> Result<Self> {
+        toml::from_str(contents)
+            .map_err(|e| ZeckitError::Config(format!("Invalid Zebra config: {}", e)))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|e| {
+            ZeckitError::Config(format!("Could not read {}: {}", path.display(), e))
+        })?;
+        Self::parse(&contents)
+            .map_err(|e| ZeckitError::Config(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(self)
+            .map_err(|e| ZeckitError::Config(format!("Could not render Zebra config: {}", e)))
+    }
+
+    pub fn set_activation_heights(&mut self, heights: ActivationHeights) {
+        self.network
+            .testnet_parameters
+            .get_or_insert_with(TestnetParameters::default)
+            .activation_heights = Some(heights);
+    }
+
+    /// Write the config to `path`, creating its directory.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(
+            path,
+            format!("# Generated by zeckit. Do not edit.\n\n{}", self.to_toml()?),
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: &str = include_str!("../../../docker/configs/zebra.toml");
+
+    #[test]
+    fn test_template_round_trip() {
+        let mut config = ZebraConfig::parse(TEMPLATE).unwrap();
+        assert_eq!(config.network.network, "Regtest");
+        assert_eq!(config.rpc.listen_addr, "0.0.0.0:8232");
+        assert!(config.mining.internal_miner);
+
+        config.mining.miner_address = Some("tmNewAddress".to_string());
+        let rendered = ZebraConfig::parse(&config.to_toml().unwrap()).unwrap();
+
+        assert_eq!(rendered.mining.miner_address.as_deref(), Some("tmNewAddress"));
+        // Sections and keys ZecKit doesn't model are kept
+        assert_eq!(rendered.other["state"]["cache_dir"].as_str(), Some("/var/zebra/state"));
+        assert_eq!(rendered.other["consensus"]["checkpoint_sync"].as_bool(), Some(false));
+        assert!(rendered.network.other.contains_key("initial_mainnet_peers"));
+    }
+
+    #[test]
+    fn test_missing_mining_section_is_created() {
+        let mut config = ZebraConfig::parse("[network]\nnetwork = \"Regtest\"\n").unwrap();
+        config.mining.miner_address = Some("tmNewAddress".to_string());
+
+        let rendered = config.to_toml().unwrap();
+        assert!(rendered.contains("[mining]"));
+        assert!(rendered.contains("miner_address = \"tmNewAddress\""));
+    }
+
+    fn activation_heights(nu5: u32, nu6: u32) -> ActivationHeights {
+        ActivationHeights {
+            overwinter: Some(1),
+            sapling: Some(1),
+            blossom: Some(1),
+            heartwood: Some(1),
+            canopy: Some(1),
+            nu5: Some(nu5),
+            nu6: Some(nu6),
+        }
+    }
+
+    #[test]
+    fn test_activation_heights() {
+        let heights = activation_heights(1, 10);
+        assert!(heights.validate().is_ok());
+        assert_eq!(
+            heights.to_env(),
+            "Overwinter=1,Sapling=1,Blossom=1,Heartwood=1,Canopy=1,NU5=1,NU6=10"
+        );
+
+        let mut config = ZebraConfig::parse(TEMPLATE).unwrap();
+        config.set_activation_heights(heights);
+
+        let rendered = config.to_toml().unwrap();
+        assert!(rendered.contains("[network.testnet_parameters.activation_heights]"));
+        assert!(rendered.contains("NU6 = 10"));
+
+        let parsed = ZebraConfig::parse(&rendered).unwrap();
+        let heights = parsed.network.testnet_parameters.unwrap().activation_heights.unwrap();
+        assert_eq!(heights.nu6, Some(10));
+
+        assert!(activation_heights(20, 10).validate().is_err());
+    }
+
+    #[test]
+    fn test_activation_heights_must_be_complete() {
+        let partial = ActivationHeights {
+            nu5: Some(1),
+            nu6: Some(10),
+            ..Default::default()
+        };
+        let error = partial.validate().unwrap_err().to_string();
+        assert!(error.contains("missing Overwinter, Sapling, Blossom, Heartwood, Canopy"), "{}", error);
+
+        // Zebra config templates may still carry partial heights
+        let template = "[network]\nnetwork = \"Regtest\"\n\n[network.testnet_parameters.activation_heights]\nNU5 = 1\n";
+        assert!(ZebraConfig::parse(template).is_ok());
+    }
+
+    #[test]
+    fn test_zingo_cli_default_heights() {
+        assert!(activation_heights(1, 1).is_zingo_cli_default());
+        assert!(!activation_heights(1, 10).is_zingo_cli_default());
+    }
+}
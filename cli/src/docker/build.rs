@@ -0,0 +1,218 @@
+//! Progress of `docker compose build`, read from BuildKit's plain output:
+//!
+//! ```text
+//! #12 [zaino builder 3/7] RUN cargo build --release
+//! #12 41.07    Compiling zaino-state v0.1.0
+//! #12 ERROR: process "/bin/sh -c cargo build --release" did not complete successfully: exit code: 101
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+
+/// Output lines kept per step for the failure report.
+pub const TAIL_LINES: usize = 40;
+
+/// One line of BuildKit's plain progress output.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BuildLine<'a> {
+    /// A step starts: `#12 [zaino builder 3/7] RUN cargo build`.
+    Step {
+        id: u32,
+        service: Option<&'a str>,
+        position: Option<(u32, u32)>,
+        name: &'a str,
+    },
+    /// Output of a running step, without its timestamp.
+    Output { id: u32, text: &'a str },
+    /// `#12 DONE 1.2s` or `#12 CACHED`.
+    Done { id: u32 },
+    /// `#12 ERROR: ...`.
+    Error { id: u32, message: &'a str },
+    /// Anything else, e.g. compose's own messages.
+    Other(&'a str),
+}
+
+pub fn parse_line(line: &str) -> BuildLine<'_> {
+    let Some((id, rest)) = line
+        .strip_prefix('#')
+        .and_then(|line| line.split_once(' '))
+        .and_then(|(id, rest)| id.parse().ok().map(|id| (id, rest)))
+    else {
+        return BuildLine::Other(line);
+    };
+
+    if let Some((label, name)) = rest.strip_prefix('[').and_then(|rest| rest.split_once("] ")) {
+        let mut words: Vec<&str> = label.split_whitespace().collect();
+        let position = words.last().and_then(|word| {
+            let (current, total) = word.split_once('/')?;
+            Some((current.parse().ok()?, total.parse().ok()?))
+        });
+        if position.is_some() {
+            words.pop();
+        }
+
+        // Compose puts the service in front of the stage (`[zebra builder 2/6]`,
+        // `[zebra internal]`); a plain `docker build` has none
+        let service = match (words.first(), position) {
+            (Some(service), Some(_)) => Some(*service),
+            (Some(service), None) if words.len() > 1 => Some(*service),
+            _ => None,
+        };
+
+        return BuildLine::Step { id, service, position, name };
+    }
+
+    if rest.starts_with("DONE") || rest == "CACHED" {
+        return BuildLine::Done { id };
+    }
+    if let Some(message) = rest.strip_prefix("ERROR:") {
+        return BuildLine::Error { id, message: message.trim() };
+    }
+
+    // Step output is prefixed with seconds since the step started
+    let text = match rest.split_once(' ') {
+        Some((elapsed, text)) if elapsed.parse::<f64>().is_ok() => text,
+        _ => rest,
+    };
+    BuildLine::Output { id, text }
+}
+
+#[derive(Debug, Default)]
+struct Step {
+    service: Option<String>,
+    position: Option<(u32, u32)>,
+    name: String,
+    tail: VecDeque<String>,
+}
+
+/// Where each service's build is and, once one fails, which step failed.
+#[derive(Debug, Default)]
+pub struct BuildProgress {
+    steps: HashMap<u32, Step>,
+    failed: Option<u32>,
+}
+
+/// The step that broke a build and its last lines of output.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BuildFailure {
+    pub service: Option<String>,
+    pub step: String,
+    pub tail: Vec<String>,
+}
+
+impl BuildProgress {
+    /// Record `line`. Returns the service and its new status when a service
+    /// starts a step.
+    pub fn feed(&mut self, line: &str) -> Option<(String, String)> {
+        match parse_line(line) {
+            BuildLine::Step { id, service, position, name } => {
+                let step = self.steps.entry(id).or_default();
+                step.service = service.map(str::to_string);
+                step.position = position;
+                step.name = name.to_string();
+
+                let service = step.service.clone()?;
+                let status = match position {
+                    Some((current, total)) => format!("[{}/{}] {}", current, total, name),
+                    None => name.to_string(),
+                };
+                return Some((service, status));
+            }
+            BuildLine::Output { id, text } => self.record(id, text),
+            BuildLine::Error { id, message } => {
+                self.record(id, message);
+                self.failed.get_or_insert(id);
+            }
+            BuildLine::Done { .. } | BuildLine::Other(_) => {}
+        }
+        None
+    }
+
+    fn record(&mut self, id: u32, text: &str) {
+        let tail = &mut self.steps.entry(id).or_default().tail;
+        if tail.len() == TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(text.to_string());
+    }
+
+    /// The first step that reported an error.
+    pub fn failure(&self) -> Option<BuildFailure> {
+        let step = &self.steps[&self.failed?];
+        let name = match step.position {
+            Some((current, total)) => format!("[{}/{}] {}", current, total, step.name),
+            None => step.name.clone(),
+        };
+
+        Some(BuildFailure {
+            service: step.service.clone(),
+            step: name,
+            tail: step.tail.iter().cloned().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("#12 [zaino builder 3/7] RUN cargo build --release"),
+            BuildLine::Step {
+                id: 12,
+                service: Some("zaino"),
+                position: Some((3, 7)),
+                name: "RUN cargo build --release",
+            }
+        );
+        assert_eq!(
+            parse_line("#1 [zebra internal] load build definition from Dockerfile"),
+            BuildLine::Step {
+                id: 1,
+                service: Some("zebra"),
+                position: None,
+                name: "load build definition from Dockerfile",
+            }
+        );
+        assert_eq!(
+            parse_line("#2 [internal] load .dockerignore"),
+            BuildLine::Step { id: 2, service: None, position: None, name: "load .dockerignore" }
+        );
+        assert_eq!(
+            parse_line("#12 41.07    Compiling zaino-state v0.1.0"),
+            BuildLine::Output { id: 12, text: "   Compiling zaino-state v0.1.0" }
+        );
+        assert_eq!(parse_line("#12 DONE 1.2s"), BuildLine::Done { id: 12 });
+        assert_eq!(parse_line("#7 CACHED"), BuildLine::Done { id: 7 });
+        assert_eq!(
+            parse_line("#12 ERROR: process did not complete successfully: exit code: 101"),
+            BuildLine::Error { id: 12, message: "process did not complete successfully: exit code: 101" }
+        );
+        assert_eq!(parse_line(" Network zeckit-network  Created"), BuildLine::Other(" Network zeckit-network  Created"));
+    }
+
+    #[test]
+    fn test_build_failure() {
+        let mut progress = BuildProgress::default();
+        assert_eq!(
+            progress.feed("#9 [zaino builder 4/7] RUN cargo build --release"),
+            Some(("zaino".to_string(), "[4/7] RUN cargo build --release".to_string()))
+        );
+        assert_eq!(progress.feed("#10 [zebra 2/5] COPY . ."), Some(("zebra".to_string(), "[2/5] COPY . .".to_string())));
+
+        for i in 0..TAIL_LINES + 5 {
+            progress.feed(&format!("#9 {}.0 line {}", i, i));
+        }
+        progress.feed("#10 DONE 0.1s");
+        assert_eq!(progress.failure(), None);
+
+        progress.feed("#9 ERROR: exit code: 101");
+        let failure = progress.failure().unwrap();
+        assert_eq!(failure.service.as_deref(), Some("zaino"));
+        assert_eq!(failure.step, "[4/7] RUN cargo build --release");
+        assert_eq!(failure.tail.len(), TAIL_LINES);
+        assert_eq!(failure.tail.last().map(String::as_str), Some("exit code: 101"));
+        assert_eq!(failure.tail.first().map(String::as_str), Some("line 6"));
+    }
+}
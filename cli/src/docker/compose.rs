@@ -1,34 +1,73 @@
-use crate::error::{Result, zeckitError};
-use std::process::{Command, Stdio};
-use std::io::{BufRead, BufReader};
+use crate::config::backend::Backend;
+use crate::config::project::ProjectConfig;
+use crate::docker::build::{BuildProgress, TAIL_LINES};
+use crate::error::{Result, ZeckitError};
+use crate::output;
+use colored::*;
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Sender};
 use std::thread;
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct DockerCompose {
-    project_dir: String,
+    project_dir: PathBuf,
+    project_name: Option<String>,
+    files: Vec<PathBuf>,
+    env: Vec<(&'static str, String)>,
 }
 
 impl DockerCompose {
-    pub fn new() -> Result<Self> {
-        // Get project root (go up from cli/ directory)
-        let current_dir = std::env::current_dir()?;
-        let project_dir = if current_dir.ends_with("cli") {
-            current_dir.parent().unwrap().to_path_buf()
-        } else {
-            current_dir
-        };
+    pub fn new(config: &ProjectConfig) -> Result<Self> {
+        let mut files = vec![config.root.join("docker-compose.yml")];
+
+        // Extra services from zeckit.toml, rendered by `zeckit up`
+        let services_file = config.services_compose_path();
+        if services_file.exists() {
+            files.push(services_file);
+        }
 
         Ok(Self {
-            project_dir: project_dir.to_string_lossy().to_string(),
+            project_dir: config.root.clone(),
+            project_name: config.compose_project(),
+            files,
+            env: config.compose_env(),
         })
     }
 
-    pub fn up(&self, services: &[&str]) -> Result<()> {
+    /// Also pass `key=value` to compose, overriding the project's value.
+    pub fn with_env(mut self, key: &'static str, value: String) -> Self {
+        self.env.retain(|(existing, _)| *existing != key);
+        self.env.push((key, value));
+        self
+    }
+
+    /// `docker compose` with the project's compose files and the variables
+    /// they interpolate.
+    fn compose(&self) -> Command {
         let mut cmd = Command::new("docker");
-        cmd.arg("compose")
-            .arg("up")
-            .arg("-d")
+        cmd.arg("compose");
+        if let Some(name) = &self.project_name {
+            cmd.arg("-p").arg(name);
+        }
+        for file in &self.files {
+            cmd.arg("-f").arg(file);
+        }
+        cmd.envs(self.env.iter().map(|(key, value)| (key, value)))
             .current_dir(&self.project_dir);
+        cmd
+    }
+
+    pub fn up(&self, services: &[&str]) -> Result<()> {
+        let mut cmd = self.compose();
+        cmd.arg("up")
+            .arg("-d");
 
         for service in services {
             cmd.arg(service);
@@ -38,61 +77,208 @@ impl DockerCompose {
 
         if !output.status.success() {
             let error = String::from_utf8_lossy(&output.stderr);
-            return Err(zeckitError::Docker(error.to_string()));
+            return Err(ZeckitError::Docker(error.to_string()));
         }
 
         Ok(())
     }
 
-    pub fn up_with_profile(&self, profile: &str) -> Result<()> {
-        println!("Building Docker images for profile '{}'...", profile);
-        println!("(This may take 10-20 minutes on first build)");
-        println!();
-        
-        // Build images silently
-        let build_status = Command::new("docker")
-            .arg("compose")
+    pub fn up_with_profile(&self, profile: &str, services: &[&str]) -> Result<()> {
+        let output = self.compose()
             .arg("--profile")
             .arg(profile)
+            .arg("up")
+            .arg("-d")
+            .args(services)
+            .output()?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(ZeckitError::Docker(error.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Build the images of `services`, showing each service's current
+    /// BuildKit step, or the raw build output with `verbose`. When the build
+    /// fails, the failing step's last lines are shown and the whole output is
+    /// written to `log_path`.
+    pub fn build(&self, profile: Option<&str>, services: &[&str], verbose: bool, log_path: &Path) -> Result<()> {
+        let mut cmd = self.compose();
+        if let Some(profile) = profile {
+            cmd.arg("--profile").arg(profile);
+        }
+
+        let mut child = cmd
             .arg("build")
-            .arg("-q")  // Quiet mode
-            .current_dir(&self.project_dir)
-            .stdout(Stdio::null())  // Discard stdout
-            .stderr(Stdio::null())  // Discard stderr
-            .status()
-            .map_err(|e| zeckitError::Docker(format!("Failed to start build: {}", e)))?;
+            .args(services)
+            // One line per event, with step numbers to follow
+            .env("BUILDKIT_PROGRESS", "plain")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ZeckitError::Docker(format!("Failed to start build: {}", e)))?;
+
+        let (sender, lines) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, sender.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, sender);
+        }
 
-        if !build_status.success() {
-            return Err(zeckitError::Docker("Image build failed".into()));
+        let bars = MultiProgress::new();
+        if verbose || !output::is_verbose() {
+            bars.set_draw_target(ProgressDrawTarget::hidden());
+        }
+        let mut service_bars: HashMap<String, ProgressBar> = HashMap::new();
+        let mut progress = BuildProgress::default();
+        let mut log = Vec::new();
+
+        for line in lines {
+            if verbose {
+                say!("{}", line);
+            }
+            if let Some((service, status)) = progress.feed(&line) {
+                let pb = service_bars
+                    .entry(service)
+                    .or_insert_with_key(|service| build_spinner(&bars, service));
+                pb.set_message(status);
+            }
+            log.push(line);
         }
 
-        println!("✓ Images built successfully");
-        println!();
+        let status = child.wait()?;
+        if status.success() {
+            service_bars.values().for_each(|pb| pb.finish_with_message("built"));
+            return Ok(());
+        }
+        service_bars.values().for_each(ProgressBar::abandon);
 
-        // Start services
-        println!("Starting containers...");
-        let output = Command::new("docker")
-            .arg("compose")
-            .arg("--profile")
-            .arg(profile)
-            .arg("up")
-            .arg("-d")
-            .current_dir(&self.project_dir)
+        if let Some(dir) = log_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(log_path, log.join("\n") + "\n")?;
+
+        let failure = progress.failure();
+        let (what, tail) = match &failure {
+            Some(failure) => (
+                format!("{} failed at {}", failure.service.as_deref().unwrap_or("Build"), failure.step),
+                failure.tail.clone(),
+            ),
+            None => ("Image build failed".to_string(), log[log.len().saturating_sub(TAIL_LINES)..].to_vec()),
+        };
+
+        if !verbose {
+            say!();
+            say!("{}", what.red());
+            for line in &tail {
+                say!("    {}", line.dimmed());
+            }
+        }
+        say!();
+
+        Err(ZeckitError::Docker(format!("{} (full log: {})", what, log_path.display())))
+    }
+
+    /// Stream the logs of `services` until the returned handle is dropped.
+    pub fn follow_logs(&self, services: &[&str]) -> Result<LogFollower> {
+        // Keep stdout clean for the JSON result
+        let stdout = if output::is_json() { Stdio::from(io::stderr()) } else { Stdio::inherit() };
+
+        let child = self.compose()
+            .args(Self::all_profiles())
+            .arg("logs")
+            .arg("--follow")
+            .args(services)
+            .stdout(stdout)
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| ZeckitError::Docker(format!("Failed to follow logs: {}", e)))?;
+
+        Ok(LogFollower(child))
+    }
+
+    /// Stop and remove the containers of `services`, in any profile.
+    pub fn remove_services(&self, services: &[&str]) -> Result<()> {
+        if services.is_empty() {
+            return Ok(());
+        }
+
+        let output = self.compose()
+            .args(Self::all_profiles())
+            .arg("rm")
+            .arg("-s")
+            .arg("-f")
+            .args(services)
             .output()?;
 
         if !output.status.success() {
             let error = String::from_utf8_lossy(&output.stderr);
-            return Err(zeckitError::Docker(error.to_string()));
+            return Err(ZeckitError::Docker(error.to_string()));
         }
 
         Ok(())
     }
 
+    /// Host port published for `service`'s `container_port`.
+    pub fn port(&self, service: &str, container_port: u16) -> Result<u16> {
+        let output = self.compose()
+            .args(Self::all_profiles())
+            .arg("port")
+            .arg(service)
+            .arg(container_port.to_string())
+            .output()?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(ZeckitError::Docker(error.to_string()));
+        }
+
+        // e.g. `127.0.0.1:9067`
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout
+            .trim()
+            .rsplit(':')
+            .next()
+            .and_then(|port| port.parse().ok())
+            .ok_or_else(|| {
+                ZeckitError::Docker(format!("{} does not publish port {}", service, container_port))
+            })
+    }
+
+    /// Names of the compose services that currently have a running container.
+    pub fn running_services(&self) -> Result<Vec<String>> {
+        let output = self.compose()
+            .args(Self::all_profiles())
+            .arg("ps")
+            .arg("--services")
+            .arg("--status")
+            .arg("running")
+            .output()?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(ZeckitError::Docker(error.to_string()));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect())
+    }
+
+    /// `--profile` flags enabling every backend, so profiled services are visible.
+    fn all_profiles() -> Vec<String> {
+        Backend::INDEXERS
+            .iter()
+            .filter_map(|backend| backend.profile())
+            .flat_map(|profile| ["--profile".to_string(), profile.to_string()])
+            .collect()
+    }
+
     pub fn down(&self, volumes: bool) -> Result<()> {
-        let mut cmd = Command::new("docker");
-        cmd.arg("compose")
-            .arg("down")
-            .current_dir(&self.project_dir);
+        let mut cmd = self.compose();
+        cmd.arg("down");
 
         if volumes {
             cmd.arg("-v");
@@ -102,49 +288,112 @@ impl DockerCompose {
 
         if !output.status.success() {
             let error = String::from_utf8_lossy(&output.stderr);
-            return Err(zeckitError::Docker(error.to_string()));
+            return Err(ZeckitError::Docker(error.to_string()));
         }
 
         Ok(())
     }
 
-    pub fn ps(&self) -> Result<Vec<String>> {
-        let output = Command::new("docker")
-            .arg("compose")
-            .arg("ps")
+    /// Stop every running container of the project, keeping containers and
+    /// volumes.
+    pub fn stop(&self) -> Result<()> {
+        let output = self.compose()
+            .args(Self::all_profiles())
+            .arg("stop")
+            .output()?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(ZeckitError::Docker(error.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Start the stopped containers of `services` again.
+    pub fn start(&self, services: &[String]) -> Result<()> {
+        if services.is_empty() {
+            return Ok(());
+        }
+
+        let output = self.compose()
+            .args(Self::all_profiles())
+            .arg("start")
+            .args(services)
+            .output()?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(ZeckitError::Docker(error.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Project name and volume names as compose resolves them.
+    pub fn project(&self) -> Result<ComposeProject> {
+        let output = self.compose()
+            .args(Self::all_profiles())
+            .arg("config")
             .arg("--format")
-            .arg("table")
-            .current_dir(&self.project_dir)
+            .arg("json")
             .output()?;
 
         if !output.status.success() {
             let error = String::from_utf8_lossy(&output.stderr);
-            return Err(zeckitError::Docker(error.to_string()));
+            return Err(ZeckitError::Docker(error.to_string()));
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let lines: Vec<String> = stdout
-            .lines()
-            .skip(1) // Skip header
-            .map(|l| l.to_string())
-            .collect();
+        Ok(serde_json::from_slice(&output.stdout)?)
+    }
 
-        Ok(lines)
+    /// Restart a running service, e.g. to pick up a re-rendered config.
+    pub fn restart(&self, service: &str) -> Result<()> {
+        let output = self.compose()
+            .arg("restart")
+            .arg(service)
+            .output()?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(ZeckitError::Docker(error.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Containers of the project, in every profile.
+    pub fn containers(&self) -> Result<Vec<ContainerState>> {
+        let output = self.compose()
+            .args(Self::all_profiles())
+            .arg("ps")
+            .arg("--all")
+            .arg("--format")
+            .arg("json")
+            .output()?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(ZeckitError::Docker(error.to_string()));
+        }
+
+        parse_containers(&String::from_utf8_lossy(&output.stdout))
     }
 
+    /// Last `tail` lines of a service's logs, in any profile.
     pub fn logs(&self, service: &str, tail: usize) -> Result<Vec<String>> {
-        let output = Command::new("docker")
-            .arg("compose")
+        let output = self.compose()
+            .args(Self::all_profiles())
             .arg("logs")
+            .arg("--no-color")
             .arg("--tail")
             .arg(tail.to_string())
             .arg(service)
-            .current_dir(&self.project_dir)
             .output()?;
 
         if !output.status.success() {
             let error = String::from_utf8_lossy(&output.stderr);
-            return Err(zeckitError::Docker(error.to_string()));
+            return Err(ZeckitError::Docker(error.to_string()));
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
@@ -153,36 +402,125 @@ impl DockerCompose {
         Ok(lines)
     }
 
-    pub fn exec(&self, service: &str, command: &[&str]) -> Result<String> {
-        let mut cmd = Command::new("docker");
-        cmd.arg("compose")
-            .arg("exec")
-            .arg("-T") // Non-interactive
-            .arg(service)
-            .current_dir(&self.project_dir);
-
-        for arg in command {
-            cmd.arg(arg);
+    /// `docker compose logs` for `service`, timestamped and without prefix
+    /// or colors, with stdout and stderr piped.
+    pub fn spawn_logs(&self, service: &str, follow: bool, since: Option<&str>) -> Result<Child> {
+        let mut cmd = self.compose();
+        cmd.args(Self::all_profiles())
+            .arg("logs")
+            .arg("--no-color")
+            .arg("--no-log-prefix")
+            .arg("--timestamps");
+        if follow {
+            cmd.arg("--follow");
+        }
+        if let Some(since) = since {
+            cmd.arg("--since").arg(since);
         }
 
-        let output = cmd.output()?;
+        cmd.arg(service)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ZeckitError::Docker(format!("Failed to read {} logs: {}", service, e)))
+    }
+}
 
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(zeckitError::Docker(error.to_string()));
-        }
+/// The parts of `docker compose config --format json` zeckit uses.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComposeProject {
+    pub name: String,
+    /// Keyed by the volume's name in the compose file.
+    #[serde(default)]
+    pub volumes: HashMap<String, ComposeVolume>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComposeVolume {
+    /// Docker volume name, e.g. `zeckit_zebra-data`.
+    pub name: String,
+}
+
+/// One container as listed by `docker compose ps --format json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContainerState {
+    #[serde(rename(deserialize = "Service"))]
+    pub service: String,
+    #[serde(rename(deserialize = "Name"))]
+    pub name: String,
+    /// e.g. `running`, `exited`
+    #[serde(rename(deserialize = "State"))]
+    pub state: String,
+    /// e.g. `Up 5 minutes (healthy)`
+    #[serde(rename(deserialize = "Status"))]
+    pub status: String,
+}
 
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+/// Compose prints one JSON object per line; releases before 2.21 print a
+/// single array instead.
+fn parse_containers(stdout: &str) -> Result<Vec<ContainerState>> {
+    let trimmed = stdout.trim();
+    if trimmed.starts_with('[') {
+        return Ok(serde_json::from_str(trimmed)?);
     }
 
-    pub fn is_running(&self) -> bool {
-        Command::new("docker")
-            .arg("compose")
-            .arg("ps")
-            .arg("-q")
-            .current_dir(&self.project_dir)
-            .output()
-            .map(|output| !output.stdout.is_empty())
-            .unwrap_or(false)
+    trimmed
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+/// A running `docker compose logs --follow`, stopped when dropped.
+pub struct LogFollower(Child);
+
+impl Drop for LogFollower {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Send each line read from `reader` to `sender`, on a thread of its own.
+fn forward_lines(reader: impl Read + Send + 'static, sender: Sender<String>) {
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(|line| line.ok()) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Spinner showing `service`'s current build step.
+fn build_spinner(bars: &MultiProgress, service: &str) -> ProgressBar {
+    let pb = bars.add(ProgressBar::new_spinner());
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("  {spinner:.green} {prefix:.bold} {wide_msg} ({elapsed})")
+            .unwrap()
+    );
+    pb.set_prefix(service.to_string());
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_containers() {
+        let line = r#"{"Name":"zeckit-zebra","Service":"zebra","State":"running","Status":"Up 2 minutes (healthy)","Ports":""}"#;
+        let expected = ContainerState {
+            service: "zebra".to_string(),
+            name: "zeckit-zebra".to_string(),
+            state: "running".to_string(),
+            status: "Up 2 minutes (healthy)".to_string(),
+        };
+
+        assert_eq!(parse_containers(&format!("{}\n{}\n", line, line)).unwrap(), vec![expected.clone(); 2]);
+        assert_eq!(parse_containers(&format!("[{}]", line)).unwrap(), vec![expected]);
+        assert!(parse_containers("").unwrap().is_empty());
     }
-}
\ No newline at end of file
+}
@@ -1,137 +1,237 @@
-use crate::error::{Result, zeckitError};
+use crate::config::backend::Backend;
+use crate::config::settings::Settings;
+use crate::docker::indexer::{proto::LightdInfo, IndexerClient};
+use crate::error::{Result, ZeckitError};
 use reqwest::Client;
 use indicatif::ProgressBar;
-use tokio::time::{sleep, Duration};
-use serde_json::Value;
-use std::net::TcpStream;
-use std::time::Duration as StdDuration;
+use tokio::time::{sleep, Duration, Instant};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use zeckit_rpc::ZebraRpcClient;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DependencyCheck {
+    pub ok: bool,
+    /// False for advisory checks, which don't affect readiness.
+    #[serde(default = "required")]
+    pub required: bool,
+    pub message: String,
+}
+
+fn required() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FaucetReadiness {
+    pub status: String,
+    pub checks: BTreeMap<String, DependencyCheck>,
+}
+
+impl FaucetReadiness {
+    /// Whether dependency `name` was reported and passed.
+    pub fn passes(&self, name: &str) -> bool {
+        self.checks.get(name).is_some_and(|check| check.ok)
+    }
+
+    /// `name: message` for every failing required dependency.
+    pub fn failures(&self) -> Vec<String> {
+        self.checks
+            .iter()
+            .filter(|(_, check)| check.required && !check.ok)
+            .map(|(name, check)| format!("{}: {}", name, check.message))
+            .collect()
+    }
+}
+
+/// Wallet figures reported by the faucet's `/stats`.
+#[derive(Debug, Deserialize)]
+pub struct FaucetStats {
+    #[serde(default)]
+    pub wallet_height: Option<u64>,
+    #[serde(default)]
+    pub faucet_address: Option<String>,
+    pub current_balance: f64,
+    pub orchard_balance: f64,
+    #[serde(default)]
+    pub transparent_balance: f64,
+}
+
+/// Overall time limit shared by consecutive waits, each of which also has
+/// its own limit.
+pub struct Deadline {
+    end: Instant,
+}
+
+impl Deadline {
+    pub fn after(total: Duration) -> Self {
+        Self {
+            end: Instant::now() + total,
+        }
+    }
+
+    /// Time a stage allowed `stage_secs` may wait without passing the deadline.
+    pub fn limit(&self, stage_secs: u64) -> Duration {
+        Duration::from_secs(stage_secs).min(self.end.saturating_duration_since(Instant::now()))
+    }
+}
 
 pub struct HealthChecker {
     client: Client,
-    max_retries: u32,
+    zebra: ZebraRpcClient,
+    faucet_url: String,
+    backend_url: String,
     retry_delay: Duration,
-    backend_max_retries: u32,
 }
 
 impl HealthChecker {
-    pub fn new() -> Self {
+    pub fn new(settings: &Settings) -> Self {
         Self {
             client: Client::new(),
-            max_retries: 560,
+            // Retries are driven by the wait_for_* loops
+            zebra: ZebraRpcClient::new(&settings.zebra_rpc_url)
+                .with_timeout(Duration::from_secs(5))
+                .with_retries(0, Duration::ZERO),
+            faucet_url: settings.faucet_api_url.clone(),
+            backend_url: settings.backend_url.clone(),
             retry_delay: Duration::from_secs(2),
-            backend_max_retries: 600,
         }
     }
 
-    pub async fn wait_for_zebra(&self, pb: &ProgressBar) -> Result<()> {
-        for i in 0..self.max_retries {
-            pb.tick();
-            
-            match self.check_zebra().await {
-                Ok(_) => return Ok(()),
-                Err(_) if i < self.max_retries - 1 => {
-                    sleep(self.retry_delay).await;
-                }
-                Err(e) => return Err(e),
-            }
-        }
-
-        Err(zeckitError::ServiceNotReady("Zebra".into()))
+    pub async fn wait_for_zebra(&self, pb: &ProgressBar, timeout: Duration) -> Result<()> {
+        self.wait_for("Zebra", pb, timeout, || self.check_zebra()).await
     }
 
-    pub async fn wait_for_faucet(&self, pb: &ProgressBar) -> Result<()> {
-        for i in 0..self.max_retries {
-            pb.tick();
-            
-            match self.check_faucet().await {
-                Ok(_) => return Ok(()),
-                Err(_) if i < self.max_retries - 1 => {
-                    sleep(self.retry_delay).await;
-                }
-                Err(e) => return Err(e),
-            }
-        }
+    pub async fn wait_for_faucet(&self, pb: &ProgressBar, timeout: Duration) -> Result<()> {
+        self.wait_for("Faucet", pb, timeout, || self.check_faucet()).await
+    }
 
-        Err(zeckitError::ServiceNotReady("Faucet".into()))
+    pub async fn wait_for_backend(
+        &self,
+        backend: Backend,
+        pb: &ProgressBar,
+        timeout: Duration,
+    ) -> Result<()> {
+        self.wait_for(backend.display_name(), pb, timeout, || self.check_backend(backend))
+            .await
     }
 
-    pub async fn wait_for_backend(&self, backend: &str, pb: &ProgressBar) -> Result<()> {
-        for i in 0..self.backend_max_retries {
+    /// Repeat `check` until it passes, showing the latest failure on `pb`.
+    /// Checks at least once, even with a zero timeout.
+    async fn wait_for<F, Fut>(
+        &self,
+        name: &str,
+        pb: &ProgressBar,
+        timeout: Duration,
+        mut check: F,
+    ) -> Result<()>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<()>>,
+    {
+        let start = Instant::now();
+
+        loop {
             pb.tick();
-            
-            match self.check_backend(backend).await {
-                Ok(_) => return Ok(()),
-                Err(_) if i < self.backend_max_retries - 1 => {
-                    sleep(self.retry_delay).await;
-                }
-                Err(e) => return Err(e),
+
+            let error = match check().await {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+
+            if start.elapsed() >= timeout {
+                return Err(ZeckitError::ServiceNotReady(format!(
+                    "{} not ready after {}s: {}",
+                    name,
+                    timeout.as_secs(),
+                    error
+                )));
             }
-        }
 
-        Err(zeckitError::ServiceNotReady(format!("{} not ready", backend)))
+            pb.set_message(format!("Waiting for {} ({})", name, error));
+            sleep(self.retry_delay).await;
+        }
     }
 
     async fn check_zebra(&self) -> Result<()> {
+        self.zebra.get_block_count().await?;
+        Ok(())
+    }
+
+    async fn check_faucet(&self) -> Result<()> {
+        // Only liveness here: readiness also needs a funded, synced wallet,
+        // which `zeckit up` establishes after the faucet has started
         let resp = self
             .client
-            .post("http://127.0.0.1:8232")
-            .json(&serde_json::json!({
-                "jsonrpc": "2.0",
-                "id": "health",
-                "method": "getblockcount",
-                "params": []
-            }))
+            .get(format!("{}/health/live", self.faucet_url))
             .timeout(Duration::from_secs(5))
             .send()
             .await?;
 
-        if resp.status().is_success() {
-            Ok(())
-        } else {
-            Err(zeckitError::HealthCheck("Zebra not ready".into()))
+        if !resp.status().is_success() {
+            return Err(ZeckitError::HealthCheck("Faucet not ready".into()));
         }
+
+        Ok(())
     }
 
-    async fn check_faucet(&self) -> Result<()> {
+    /// Per-dependency readiness reported by the faucet's `/health/ready`.
+    pub async fn faucet_readiness(&self) -> Result<FaucetReadiness> {
+        // 503 still carries the readiness report, so the status is not checked
+        let resp = self
+            .client
+            .get(format!("{}/health/ready", self.faucet_url))
+            .timeout(Duration::from_secs(15))
+            .send()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Wallet height and balance of the faucet.
+    pub async fn faucet_stats(&self) -> Result<FaucetStats> {
         let resp = self
             .client
-            .get("http://127.0.0.1:8080/health")
+            .get(format!("{}/stats", self.faucet_url))
             .timeout(Duration::from_secs(5))
             .send()
             .await?;
 
         if !resp.status().is_success() {
-            return Err(zeckitError::HealthCheck("Faucet not ready".into()));
+            return Err(ZeckitError::HealthCheck(format!(
+                "Faucet stats returned {}",
+                resp.status()
+            )));
         }
 
-        let json: Value = resp.json().await?;
-        
-        if json.get("status").and_then(|s| s.as_str()) == Some("unhealthy") {
-            return Err(zeckitError::HealthCheck("Faucet unhealthy".into()));
-        }
+        Ok(resp.json().await?)
+    }
 
-        Ok(())
+    /// Version, chain and tip reported by the indexer's `GetLightdInfo`.
+    pub async fn indexer_info(&self) -> Result<LightdInfo> {
+        IndexerClient::connect(&self.backend_url, Duration::from_secs(5))
+            .await?
+            .get_lightd_info()
+            .await
     }
 
-    async fn check_backend(&self, backend: &str) -> Result<()> {
-        // Zaino and Lightwalletd are gRPC services on port 9067
-        // They don't respond to HTTP, so we do a TCP connection check
-        
-        let backend_name = if backend == "lwd" { "lightwalletd" } else { "zaino" };
-        
-        // Try to connect to localhost:9067 with 2 second timeout
-        match TcpStream::connect_timeout(
-            &"127.0.0.1:9067".parse().unwrap(),
-            StdDuration::from_secs(2)
-        ) {
-            Ok(_) => {
-                // Port is open and accepting connections - backend is ready!
-                Ok(())
-            }
-            Err(_) => {
-                // Port not accepting connections yet
-                Err(zeckitError::HealthCheck(format!("{} not ready", backend_name)))
-            }
+    async fn check_backend(&self, backend: Backend) -> Result<()> {
+        // An open port only means the indexer has started; it is ready once
+        // its gRPC API answers and it has caught up with Zebra
+        let backend_name = backend.display_name();
+        let zebra_height = self.zebra.get_block_count().await?;
+
+        let mut indexer = IndexerClient::connect(&self.backend_url, Duration::from_secs(5)).await?;
+        indexer.get_lightd_info().await?;
+        let tip = indexer.get_latest_block().await?.height;
+
+        if tip < zebra_height {
+            return Err(ZeckitError::HealthCheck(format!(
+                "{} at block {} of {}",
+                backend_name, tip, zebra_height
+            )));
         }
+
+        Ok(())
     }
-}
\ No newline at end of file
+}
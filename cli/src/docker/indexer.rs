@@ -0,0 +1,395 @@
+use crate::error::{Result, ZeckitError};
+use tokio::time::Duration;
+use tonic::client::Grpc;
+use tonic::codec::ProstCodec;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::{Channel, Endpoint};
+
+/// Messages of lightwalletd's `service.proto` and `compact_formats.proto`
+/// (package `cash.z.wallet.sdk.rpc`), limited to the calls used for health
+/// checks and `zeckit test --parity`. Zaino serves the same
+/// `CompactTxStreamer` service. Byte fields serialize as hex.
+pub mod proto {
+    use serde::{Serialize, Serializer};
+
+    fn hex<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+        serializer.serialize_str(&hex)
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Empty {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ChainSpec {}
+
+    #[derive(Clone, PartialEq, Serialize, prost::Message)]
+    pub struct BlockId {
+        #[prost(uint64, tag = "1")]
+        pub height: u64,
+        #[prost(bytes = "vec", tag = "2")]
+        #[serde(serialize_with = "hex")]
+        pub hash: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct BlockRange {
+        #[prost(message, optional, tag = "1")]
+        pub start: Option<BlockId>,
+        #[prost(message, optional, tag = "2")]
+        pub end: Option<BlockId>,
+    }
+
+    #[derive(Clone, PartialEq, Serialize, prost::Message)]
+    pub struct CompactBlock {
+        #[prost(uint32, tag = "1")]
+        pub proto_version: u32,
+        #[prost(uint64, tag = "2")]
+        pub height: u64,
+        #[prost(bytes = "vec", tag = "3")]
+        #[serde(serialize_with = "hex")]
+        pub hash: Vec<u8>,
+        #[prost(bytes = "vec", tag = "4")]
+        #[serde(serialize_with = "hex")]
+        pub prev_hash: Vec<u8>,
+        #[prost(uint32, tag = "5")]
+        pub time: u32,
+        #[prost(bytes = "vec", tag = "6")]
+        #[serde(serialize_with = "hex")]
+        pub header: Vec<u8>,
+        #[prost(message, repeated, tag = "7")]
+        pub vtx: Vec<CompactTx>,
+        #[prost(message, optional, tag = "8")]
+        pub chain_metadata: Option<ChainMetadata>,
+    }
+
+    #[derive(Clone, PartialEq, Serialize, prost::Message)]
+    pub struct ChainMetadata {
+        #[prost(uint32, tag = "1")]
+        pub sapling_commitment_tree_size: u32,
+        #[prost(uint32, tag = "2")]
+        pub orchard_commitment_tree_size: u32,
+    }
+
+    #[derive(Clone, PartialEq, Serialize, prost::Message)]
+    pub struct CompactTx {
+        #[prost(uint64, tag = "1")]
+        pub index: u64,
+        #[prost(bytes = "vec", tag = "2")]
+        #[serde(serialize_with = "hex")]
+        pub hash: Vec<u8>,
+        #[prost(uint32, tag = "3")]
+        pub fee: u32,
+        #[prost(message, repeated, tag = "4")]
+        pub spends: Vec<CompactSaplingSpend>,
+        #[prost(message, repeated, tag = "5")]
+        pub outputs: Vec<CompactSaplingOutput>,
+        #[prost(message, repeated, tag = "6")]
+        pub actions: Vec<CompactOrchardAction>,
+    }
+
+    #[derive(Clone, PartialEq, Serialize, prost::Message)]
+    pub struct CompactSaplingSpend {
+        #[prost(bytes = "vec", tag = "1")]
+        #[serde(serialize_with = "hex")]
+        pub nf: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, Serialize, prost::Message)]
+    pub struct CompactSaplingOutput {
+        #[prost(bytes = "vec", tag = "1")]
+        #[serde(serialize_with = "hex")]
+        pub cmu: Vec<u8>,
+        #[prost(bytes = "vec", tag = "2")]
+        #[serde(serialize_with = "hex")]
+        pub ephemeral_key: Vec<u8>,
+        #[prost(bytes = "vec", tag = "3")]
+        #[serde(serialize_with = "hex")]
+        pub ciphertext: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, Serialize, prost::Message)]
+    pub struct CompactOrchardAction {
+        #[prost(bytes = "vec", tag = "1")]
+        #[serde(serialize_with = "hex")]
+        pub nullifier: Vec<u8>,
+        #[prost(bytes = "vec", tag = "2")]
+        #[serde(serialize_with = "hex")]
+        pub cmx: Vec<u8>,
+        #[prost(bytes = "vec", tag = "3")]
+        #[serde(serialize_with = "hex")]
+        pub ephemeral_key: Vec<u8>,
+        #[prost(bytes = "vec", tag = "4")]
+        #[serde(serialize_with = "hex")]
+        pub ciphertext: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, Serialize, prost::Message)]
+    pub struct TreeState {
+        #[prost(string, tag = "1")]
+        pub network: String,
+        #[prost(uint64, tag = "2")]
+        pub height: u64,
+        #[prost(string, tag = "3")]
+        pub hash: String,
+        #[prost(uint32, tag = "4")]
+        pub time: u32,
+        #[prost(string, tag = "5")]
+        pub sapling_tree: String,
+        #[prost(string, tag = "6")]
+        pub orchard_tree: String,
+    }
+
+    /// Selects a transaction by `hash` (txid in internal byte order).
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TxFilter {
+        #[prost(message, optional, tag = "1")]
+        pub block: Option<BlockId>,
+        #[prost(uint64, tag = "2")]
+        pub index: u64,
+        #[prost(bytes = "vec", tag = "3")]
+        pub hash: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, Serialize, prost::Message)]
+    pub struct RawTransaction {
+        #[prost(bytes = "vec", tag = "1")]
+        #[serde(serialize_with = "hex")]
+        pub data: Vec<u8>,
+        #[prost(uint64, tag = "2")]
+        pub height: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct GetAddressUtxosArg {
+        #[prost(string, repeated, tag = "1")]
+        pub addresses: Vec<String>,
+        #[prost(uint64, tag = "2")]
+        pub start_height: u64,
+        #[prost(uint32, tag = "3")]
+        pub max_entries: u32,
+    }
+
+    #[derive(Clone, PartialEq, Serialize, prost::Message)]
+    pub struct GetAddressUtxosReply {
+        #[prost(bytes = "vec", tag = "1")]
+        #[serde(serialize_with = "hex")]
+        pub txid: Vec<u8>,
+        #[prost(int32, tag = "2")]
+        pub index: i32,
+        #[prost(bytes = "vec", tag = "3")]
+        #[serde(serialize_with = "hex")]
+        pub script: Vec<u8>,
+        #[prost(int64, tag = "4")]
+        pub value_zat: i64,
+        #[prost(uint64, tag = "5")]
+        pub height: u64,
+        #[prost(string, tag = "6")]
+        pub address: String,
+    }
+
+    #[derive(Clone, PartialEq, Serialize, prost::Message)]
+    pub struct GetAddressUtxosReplyList {
+        #[prost(message, repeated, tag = "1")]
+        pub address_utxos: Vec<GetAddressUtxosReply>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct LightdInfo {
+        #[prost(string, tag = "1")]
+        pub version: String,
+        #[prost(string, tag = "2")]
+        pub vendor: String,
+        #[prost(bool, tag = "3")]
+        pub taddr_support: bool,
+        #[prost(string, tag = "4")]
+        pub chain_name: String,
+        #[prost(uint64, tag = "5")]
+        pub sapling_activation_height: u64,
+        #[prost(string, tag = "6")]
+        pub consensus_branch_id: String,
+        #[prost(uint64, tag = "7")]
+        pub block_height: u64,
+        #[prost(string, tag = "8")]
+        pub git_commit: String,
+        #[prost(string, tag = "9")]
+        pub branch: String,
+        #[prost(string, tag = "10")]
+        pub build_date: String,
+        #[prost(string, tag = "11")]
+        pub build_user: String,
+        #[prost(uint64, tag = "12")]
+        pub estimated_height: u64,
+        #[prost(string, tag = "13")]
+        pub zcashd_build: String,
+        #[prost(string, tag = "14")]
+        pub zcashd_subversion: String,
+    }
+}
+
+const GET_LIGHTD_INFO: &str = "/cash.z.wallet.sdk.rpc.CompactTxStreamer/GetLightdInfo";
+const GET_LATEST_BLOCK: &str = "/cash.z.wallet.sdk.rpc.CompactTxStreamer/GetLatestBlock";
+const GET_BLOCK_RANGE: &str = "/cash.z.wallet.sdk.rpc.CompactTxStreamer/GetBlockRange";
+const GET_TREE_STATE: &str = "/cash.z.wallet.sdk.rpc.CompactTxStreamer/GetTreeState";
+const GET_TRANSACTION: &str = "/cash.z.wallet.sdk.rpc.CompactTxStreamer/GetTransaction";
+const GET_ADDRESS_UTXOS: &str = "/cash.z.wallet.sdk.rpc.CompactTxStreamer/GetAddressUtxos";
+
+/// Minimal gRPC client for an indexer's `CompactTxStreamer` service.
+pub struct IndexerClient {
+    grpc: Grpc<Channel>,
+}
+
+impl IndexerClient {
+    /// Connect to `url` (e.g. `http://127.0.0.1:9067`). `timeout` bounds
+    /// both the connection and each call.
+    pub async fn connect(url: &str, timeout: Duration) -> Result<Self> {
+        let channel = Endpoint::from_shared(url.to_string())
+            .map_err(|e| ZeckitError::Config(format!("Invalid indexer URL {}: {}", url, e)))?
+            .connect_timeout(timeout)
+            .timeout(timeout)
+            .connect()
+            .await
+            .map_err(|e| ZeckitError::HealthCheck(format!("Indexer unreachable at {}: {}", url, e)))?;
+
+        Ok(Self {
+            grpc: Grpc::new(channel),
+        })
+    }
+
+    pub async fn get_lightd_info(&mut self) -> Result<proto::LightdInfo> {
+        self.unary(GET_LIGHTD_INFO, proto::Empty {}).await
+    }
+
+    /// Height and hash of the indexer's chain tip.
+    pub async fn get_latest_block(&mut self) -> Result<proto::BlockId> {
+        self.unary(GET_LATEST_BLOCK, proto::ChainSpec {}).await
+    }
+
+    /// Compact blocks from `start` to `end`, inclusive.
+    pub async fn get_block_range(&mut self, start: u64, end: u64) -> Result<Vec<proto::CompactBlock>> {
+        let range = proto::BlockRange {
+            start: Some(proto::BlockId { height: start, hash: Vec::new() }),
+            end: Some(proto::BlockId { height: end, hash: Vec::new() }),
+        };
+        self.server_streaming(GET_BLOCK_RANGE, range).await
+    }
+
+    /// Sapling and Orchard note commitment trees as of block `height`.
+    pub async fn get_tree_state(&mut self, height: u64) -> Result<proto::TreeState> {
+        let block = proto::BlockId { height, hash: Vec::new() };
+        self.unary(GET_TREE_STATE, block).await
+    }
+
+    /// A transaction by txid, given in display (big-endian) hex.
+    pub async fn get_transaction(&mut self, txid: &str) -> Result<proto::RawTransaction> {
+        let mut hash = (0..txid.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(txid.get(i..i + 2).unwrap_or_default(), 16))
+            .collect::<std::result::Result<Vec<u8>, _>>()
+            .map_err(|_| ZeckitError::Config(format!("Invalid txid {}", txid)))?;
+        hash.reverse();
+
+        let filter = proto::TxFilter { block: None, index: 0, hash };
+        self.unary(GET_TRANSACTION, filter).await
+    }
+
+    /// Unspent transparent outputs of `addresses` created at or after
+    /// `start_height`.
+    pub async fn get_address_utxos(
+        &mut self,
+        addresses: &[String],
+        start_height: u64,
+    ) -> Result<proto::GetAddressUtxosReplyList> {
+        let arg = proto::GetAddressUtxosArg {
+            addresses: addresses.to_vec(),
+            start_height,
+            max_entries: 0,
+        };
+        self.unary(GET_ADDRESS_UTXOS, arg).await
+    }
+
+    async fn unary<Req, Resp>(&mut self, path: &'static str, request: Req) -> Result<Resp>
+    where
+        Req: prost::Message + 'static,
+        Resp: prost::Message + Default + 'static,
+    {
+        self.ready().await?;
+
+        let response = self
+            .grpc
+            .unary(
+                tonic::Request::new(request),
+                PathAndQuery::from_static(path),
+                ProstCodec::default(),
+            )
+            .await
+            .map_err(|status| call_failed(path, status))?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn server_streaming<Req, Resp>(&mut self, path: &'static str, request: Req) -> Result<Vec<Resp>>
+    where
+        Req: prost::Message + 'static,
+        Resp: prost::Message + Default + 'static,
+    {
+        self.ready().await?;
+
+        let mut stream = self
+            .grpc
+            .server_streaming(
+                tonic::Request::new(request),
+                PathAndQuery::from_static(path),
+                ProstCodec::default(),
+            )
+            .await
+            .map_err(|status| call_failed(path, status))?
+            .into_inner();
+
+        let mut messages = Vec::new();
+        while let Some(message) = stream.message().await.map_err(|status| call_failed(path, status))? {
+            messages.push(message);
+        }
+        Ok(messages)
+    }
+
+    async fn ready(&mut self) -> Result<()> {
+        self.grpc
+            .ready()
+            .await
+            .map_err(|e| ZeckitError::HealthCheck(format!("Indexer not ready: {}", e)))
+    }
+}
+
+fn call_failed(path: &str, status: tonic::Status) -> ZeckitError {
+    ZeckitError::HealthCheck(format!(
+        "{} failed: {}",
+        path.rsplit('/').next().unwrap_or(path),
+        status.message()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::proto::*;
+    use prost::Message;
+
+    #[test]
+    fn test_lightd_info_wire_format() {
+        // version = "v0.4.17", chainName = "regtest", blockHeight = 150
+        let encoded = [
+            0x0a, 0x07, b'v', b'0', b'.', b'4', b'.', b'1', b'7',
+            0x22, 0x07, b'r', b'e', b'g', b't', b'e', b's', b't',
+            0x38, 0x96, 0x01,
+        ];
+
+        let info = LightdInfo::decode(&encoded[..]).unwrap();
+        assert_eq!(info.version, "v0.4.17");
+        assert_eq!(info.chain_name, "regtest");
+        assert_eq!(info.block_height, 150);
+        assert!(info.vendor.is_empty());
+
+        let block = BlockId { height: 150, hash: vec![0xab; 32] };
+        assert_eq!(BlockId::decode(block.encode_to_vec().as_slice()).unwrap(), block);
+    }
+}
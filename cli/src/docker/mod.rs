@@ -1,2 +1,5 @@
+pub mod build;
 pub mod compose;
-pub mod health;
\ No newline at end of file
+pub mod health;
+pub mod indexer;
+pub mod zingo;
@@ -0,0 +1,330 @@
+use crate::config::backend::Backend;
+use crate::config::project::ProjectConfig;
+use crate::docker::compose::DockerCompose;
+use crate::error::{Result, ZeckitError};
+use serde::Deserialize;
+use serde_json::Value;
+use std::process::Command as StdCommand;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::process::Command;
+
+/// Data dir of the zingo-wallet container's own wallet, which Zebra mines to.
+const DEVNET_DATA_DIR: &str = "/var/zingo";
+
+/// A zingo-cli wallet in the devnet's zingo-wallet container. Each call runs
+/// one zingo-cli command (no interactive session) and parses its output.
+pub struct ZingoCli {
+    container: String,
+    server: &'static str,
+    data_dir: String,
+    /// Remove `data_dir` when dropped.
+    temporary: bool,
+}
+
+/// An entry of zingo-cli's `addresses`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WalletAddress {
+    /// Unified address.
+    pub address: String,
+    #[serde(default)]
+    pub receivers: Receivers,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Receivers {
+    #[serde(default)]
+    pub transparent: Option<String>,
+    #[serde(default)]
+    pub sapling: Option<String>,
+    #[serde(default)]
+    pub orchard_exists: bool,
+}
+
+/// An entry of zingo-cli's `t_addresses`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransparentAddress {
+    pub encoded_address: String,
+}
+
+/// Confirmed balance per pool, in zatoshis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalletBalance {
+    pub transparent: u64,
+    pub sapling: u64,
+    pub orchard: u64,
+}
+
+impl ZingoCli {
+    /// The zingo-wallet container's own wallet, served by `backend`.
+    pub fn devnet(config: &ProjectConfig, backend: Backend) -> Self {
+        Self {
+            container: config.container("zingo-wallet"),
+            server: backend.uri().unwrap_or_default(),
+            data_dir: DEVNET_DATA_DIR.to_string(),
+            temporary: false,
+        }
+    }
+
+    /// A new, empty wallet in a temporary directory of the zingo-wallet
+    /// container, removed again when dropped. `birthday` is the height its
+    /// scan starts from.
+    pub async fn ephemeral(config: &ProjectConfig, label: &str, birthday: u64) -> Result<Self> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default();
+
+        let wallet = Self {
+            container: config.container("zingo-wallet"),
+            server: running_indexer(config)?.uri().unwrap_or_default(),
+            data_dir: format!("/tmp/zeckit-{}-{}", label, nanos),
+            temporary: true,
+        };
+
+        // zingo-cli creates a wallet on first use of a data dir
+        wallet
+            .run(&["--birthday", &birthday.to_string()], "addresses", &[], false)
+            .await?;
+        Ok(wallet)
+    }
+
+    /// Run `command` and return its stdout. Fails on a non-zero exit status
+    /// or a JSON `{"error": ...}` reply.
+    async fn run(&self, options: &[&str], command: &str, args: &[&str], sync: bool) -> Result<String> {
+        let mut cmd = Command::new("docker");
+        cmd.args(["exec", &self.container, "zingo-cli"])
+            .args(["--data-dir", &self.data_dir, "--server", self.server, "--chain", "regtest"])
+            .args(options);
+        if !sync {
+            cmd.arg("--nosync");
+        }
+
+        let output = cmd.arg(command).args(args).output().await.map_err(|e| {
+            ZeckitError::Docker(format!("Failed to run zingo-cli {}: {}", command, e))
+        })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let detail = if stderr.trim().is_empty() { &stdout } else { stderr.trim() };
+            return Err(ZeckitError::Docker(format!("zingo-cli {} failed: {}", command, detail)));
+        }
+
+        if let Ok(Value::Object(reply)) = serde_json::from_str::<Value>(&stdout) {
+            if let Some(error) = reply.get("error") {
+                let error = error.as_str().map(str::to_string).unwrap_or_else(|| error.to_string());
+                return Err(ZeckitError::Docker(format!("zingo-cli {}: {}", command, error)));
+            }
+        }
+
+        Ok(stdout)
+    }
+
+    pub async fn addresses(&self) -> Result<Vec<WalletAddress>> {
+        let stdout = self.run(&[], "addresses", &[], false).await?;
+        serde_json::from_str(&stdout).map_err(|e| {
+            ZeckitError::Docker(format!("Unexpected zingo-cli addresses output: {}", e))
+        })
+    }
+
+    pub async fn transparent_addresses(&self) -> Result<Vec<TransparentAddress>> {
+        let stdout = self.run(&[], "t_addresses", &[], false).await?;
+        serde_json::from_str(&stdout).map_err(|e| {
+            ZeckitError::Docker(format!("Unexpected zingo-cli t_addresses output: {}", e))
+        })
+    }
+
+    /// The first transparent address.
+    pub async fn transparent_address(&self) -> Result<String> {
+        self.transparent_addresses()
+            .await?
+            .into_iter()
+            .next()
+            .map(|address| address.encoded_address)
+            .ok_or_else(|| ZeckitError::Docker("zingo-cli wallet has no transparent addresses".into()))
+    }
+
+    /// The first unified address and its receivers.
+    pub async fn address(&self) -> Result<WalletAddress> {
+        self.addresses()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ZeckitError::Docker("zingo-cli wallet has no addresses".into()))
+    }
+
+    /// Scan the chain up to the indexer's tip.
+    pub async fn sync(&self) -> Result<()> {
+        self.run(&[], "sync", &["run"], true).await?;
+        Ok(())
+    }
+
+    pub async fn balance(&self) -> Result<WalletBalance> {
+        let stdout = self.run(&[], "balance", &[], false).await?;
+        parse_balance(&stdout)
+    }
+
+    /// Send `zatoshis` to `address`, returning the txid.
+    pub async fn send(&self, address: &str, zatoshis: u64, memo: Option<&str>) -> Result<String> {
+        let mut payment = serde_json::json!({ "address": address, "amount": zatoshis });
+        if let Some(memo) = memo {
+            payment["memo"] = Value::from(memo);
+        }
+        let payments = Value::Array(vec![payment]).to_string();
+
+        let stdout = self.run(&[], "quick_send", &[&payments], true).await?;
+        parse_txid("quick_send", &stdout)
+    }
+
+    /// Move the wallet's transparent funds to its Orchard pool, returning the
+    /// txid.
+    pub async fn shield(&self) -> Result<String> {
+        let stdout = self.run(&[], "quick_shield", &[], true).await?;
+        parse_txid("quick_shield", &stdout)
+    }
+
+    /// Text memos of the messages the wallet has received.
+    pub async fn memos(&self) -> Result<Vec<String>> {
+        let stdout = self.run(&[], "messages", &[], false).await?;
+        let reply: Value = serde_json::from_str(&stdout).map_err(|e| {
+            ZeckitError::Docker(format!("Unexpected zingo-cli messages output: {}", e))
+        })?;
+
+        let mut memos = Vec::new();
+        collect_memos(&reply, &mut memos);
+        Ok(memos)
+    }
+}
+
+impl Drop for ZingoCli {
+    fn drop(&mut self) {
+        if !self.temporary {
+            return;
+        }
+
+        let _ = StdCommand::new("docker")
+            .args(["exec", &self.container, "rm", "-rf", &self.data_dir])
+            .output();
+    }
+}
+
+/// The indexer the devnet is running, which the wallet must use.
+pub fn running_indexer(config: &ProjectConfig) -> Result<Backend> {
+    let containers = DockerCompose::new(config)?.containers()?;
+
+    Backend::INDEXERS
+        .into_iter()
+        .find(|backend| {
+            backend.indexer_service().is_some_and(|service| {
+                containers
+                    .iter()
+                    .any(|c| c.service == service && c.state == "running")
+            })
+        })
+        .ok_or_else(|| {
+            ZeckitError::HealthCheck("No backend detected (neither zaino nor lightwalletd running)".into())
+        })
+}
+
+/// `balance` prints a JSON object with the balance of each pool in
+/// zatoshis; anything else is an error.
+fn parse_balance(output: &str) -> Result<WalletBalance> {
+    #[derive(Deserialize)]
+    struct Reply {
+        confirmed_transparent_balance: u64,
+        confirmed_sapling_balance: u64,
+        confirmed_orchard_balance: u64,
+    }
+
+    let reply: Reply = serde_json::from_str(output).map_err(|e| {
+        ZeckitError::Docker(format!("Unexpected zingo-cli balance output: {}: {}", e, output))
+    })?;
+
+    Ok(WalletBalance {
+        transparent: reply.confirmed_transparent_balance,
+        sapling: reply.confirmed_sapling_balance,
+        orchard: reply.confirmed_orchard_balance,
+    })
+}
+
+/// The txid in a `quick_send` or `quick_shield` reply.
+fn parse_txid(command: &str, output: &str) -> Result<String> {
+    let reply: Value = serde_json::from_str(output).map_err(|e| {
+        ZeckitError::Docker(format!("Unexpected zingo-cli {} output: {}", command, e))
+    })?;
+
+    reply
+        .get("txids")
+        .and_then(|txids| txids.get(0))
+        .or_else(|| reply.get("txid"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| ZeckitError::Docker(format!("zingo-cli {} returned no txid: {}", command, reply)))
+}
+
+/// Every non-empty `memo` string (or `memos` entry) in `value`.
+fn collect_memos(value: &Value, memos: &mut Vec<String>) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| collect_memos(item, memos)),
+        Value::Object(fields) => {
+            for (name, field) in fields {
+                match (name.as_str(), field) {
+                    ("memo", Value::String(memo)) if !memo.is_empty() => memos.push(memo.clone()),
+                    ("memos", Value::Array(items)) => memos.extend(
+                        items
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .filter(|memo| !memo.is_empty())
+                            .map(str::to_string),
+                    ),
+                    _ => collect_memos(field, memos),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_balance() {
+        let json = r#"{"confirmed_transparent_balance": 1250000000, "confirmed_sapling_balance": 5000, "confirmed_orchard_balance": 7, "unverified_orchard_balance": 0}"#;
+        assert_eq!(
+            parse_balance(json).unwrap(),
+            WalletBalance { transparent: 1_250_000_000, sapling: 5000, orchard: 7 }
+        );
+
+        // The old `name: value` text output is no longer accepted
+        let text = "[\n confirmed_transparent_balance: 1_250_000_000\n confirmed_sapling_balance: 0\n confirmed_orchard_balance: 300_000\n]";
+        assert!(parse_balance(text).is_err());
+        assert!(parse_balance(r#"{"confirmed_orchard_balance": 7}"#).is_err());
+        assert!(parse_balance("Error: wallet locked").is_err());
+    }
+
+    #[test]
+    fn test_parse_txid() {
+        assert_eq!(parse_txid("quick_shield", r#"{"txids": ["ab01", "cd02"]}"#).unwrap(), "ab01");
+        assert_eq!(parse_txid("quick_send", r#"{"txid": "ef03"}"#).unwrap(), "ef03");
+        assert!(parse_txid("quick_shield", r#"{"txids": []}"#).is_err());
+        assert!(parse_txid("quick_shield", "Shielded 1 ZEC").is_err());
+    }
+
+    #[test]
+    fn test_collect_memos() {
+        let reply: Value = serde_json::from_str(
+            r#"{"value_transfers": [
+                {"kind": "received", "memos": ["zeckit orchard", ""]},
+                {"kind": "received", "memo": "zeckit sapling"},
+                {"kind": "sent", "recipient": {"memo": null}}
+            ]}"#,
+        )
+        .unwrap();
+
+        let mut memos = Vec::new();
+        collect_memos(&reply, &mut memos);
+        assert_eq!(memos, ["zeckit orchard", "zeckit sapling"]);
+    }
+}
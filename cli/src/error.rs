@@ -1,9 +1,9 @@
 use thiserror::Error;
 
-pub type Result<T> = std::result::Result<T, zeckitError>;
+pub type Result<T> = std::result::Result<T, ZeckitError>;
 
 #[derive(Error, Debug)]
-pub enum zeckitError {
+pub enum ZeckitError {
     #[error("Docker error: {0}")]
     Docker(String),
     
@@ -24,4 +24,7 @@ pub enum zeckitError {
     
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
+
+    #[error("Zebra RPC error: {0}")]
+    Rpc(#[from] zeckit_rpc::RpcError),
 }
\ No newline at end of file
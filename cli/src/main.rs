@@ -1,35 +1,76 @@
 use clap::{Parser, Subcommand};
 use colored::*;
+use config::backend::Backend;
+use output::OutputFormat;
+use report::ReportTarget;
+use std::path::PathBuf;
 use std::process;
 
+#[macro_use]
+mod output;
 mod commands;
 mod docker;
 mod config;
 mod error;
+mod report;
+mod smoke;
 mod utils;
 
-use error::Result;
-
 #[derive(Parser)]
 #[command(name = "zeckit")]
 #[command(about = "ZecKit - Developer toolkit for Zcash on Zebra", long_about = None)]
 #[command(version)]
 struct Cli {
+    /// Name of an isolated devnet, with its own containers, volumes and ports
+    #[arg(long, global = true, env = "ZECKIT_PROJECT")]
+    name: Option<String>,
+    
+    /// Output format. With `json`, stdout carries a single JSON document
+    /// (for `up`, `status`, `test`, `doctor` and `snapshot save`) and
+    /// progress text goes to stderr
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+    
+    /// Suppress progress and informational text
+    #[arg(short, long, global = true)]
+    quiet: bool,
+    
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Scaffold a ZecKit project (compose file, Dockerfiles, configs, zeckit.toml)
+    Init {
+        /// Directory to initialize (defaults to the current directory)
+        dir: Option<PathBuf>,
+        
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
+    },
+    
     /// Start the ZecKit devnet
     Up {
-        /// Light-client backend: lwd (lightwalletd) or zaino
-        #[arg(short, long, default_value = "none")]
-        backend: String,
+        /// Light-client backend: lwd (lightwalletd), zaino, or none (Zebra only).
+        /// Defaults to `devnet.backend` in zeckit.toml
+        #[arg(short, long, value_enum)]
+        backend: Option<Backend>,
         
         /// Force fresh start (remove volumes)
         #[arg(short, long)]
         fresh: bool,
+        
+        /// Overall startup timeout in seconds. Defaults to `timeouts.total`
+        /// in zeckit.toml
+        #[arg(long)]
+        timeout: Option<u64>,
+        
+        /// Stream the raw image build output and, while waiting for the
+        /// devnet, the container logs
+        #[arg(short, long)]
+        verbose: bool,
     },
     
     /// Stop the ZecKit devnet
@@ -42,26 +83,151 @@ enum Commands {
     /// Show devnet status
     Status,
     
+    /// Mine blocks on the devnet
+    Mine {
+        /// Number of blocks to mine (default: 1)
+        count: Option<u32>,
+        
+        /// Mine until the chain reaches this height
+        #[arg(long, conflicts_with = "count")]
+        until_height: Option<u64>,
+        
+        /// Transparent address to receive the coinbase. Becomes the devnet's
+        /// miner address (Zebra is restarted to apply it)
+        #[arg(long)]
+        address: Option<String>,
+    },
+    
+    /// Check Docker, ports, disk, memory and leftover containers
+    Doctor,
+    
+    /// Save or restore the devnet's chain and wallet state
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+    
+    /// Show service logs, or bundle them for a bug report
+    Logs {
+        /// Services to show, by name or prefix (e.g. `faucet`). Default: all
+        services: Vec<String>,
+        
+        /// Keep streaming new log lines
+        #[arg(short, long)]
+        follow: bool,
+        
+        /// Only logs since this time: a duration (`10m`) or a timestamp
+        #[arg(long)]
+        since: Option<String>,
+        
+        /// Only lines matching this regular expression
+        #[arg(long, value_name = "PATTERN")]
+        grep: Option<String>,
+        
+        /// Write logs, containers, configs and status to a .tar.gz for a bug report
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["follow", "grep"])]
+        bundle: Option<PathBuf>,
+    },
+    
     /// Run smoke tests
-    Test,
+    Test {
+        /// Only run tests whose name contains PATTERN (and their dependencies)
+        #[arg(long, value_name = "PATTERN")]
+        filter: Option<String>,
+
+        /// Only run tests with this tag (and their dependencies). May be repeated
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// List the selected tests instead of running them
+        #[arg(long)]
+        list: bool,
+
+        /// Most tests to run at once [default: all independent tests]
+        #[arg(long, value_name = "N")]
+        jobs: Option<usize>,
+
+        /// Stop starting new tests after the first failure
+        #[arg(long)]
+        fail_fast: bool,
+
+        /// Run the steps of a YAML or TOML scenario file instead of the
+        /// built-in tests. May be repeated
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["filter", "tags", "list", "jobs"])]
+        scenario: Vec<PathBuf>,
+
+        /// Compare lightwalletd and Zaino: start whichever isn't running,
+        /// send both the same CompactTxStreamer calls and report differences
+        #[arg(long, conflicts_with_all = ["filter", "tags", "list", "jobs", "scenario"])]
+        parity: bool,
+
+        /// Write a report: `junit=<path>` or `tap[=<path>]` (stdout when no
+        /// path is given). May be repeated
+        #[arg(long, value_name = "FORMAT[=PATH]")]
+        report: Vec<ReportTarget>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SnapshotAction {
+    /// Stop the devnet, archive its volumes and configs to
+    /// .zeckit/snapshots/<SNAPSHOT>.tar.gz, and start it again
+    Save {
+        snapshot: String,
+    },
+    
+    /// Replace the devnet's volumes with a saved snapshot and start it
+    Restore {
+        snapshot: String,
+    },
 }
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    output::init(cli.output, cli.quiet);
     
     let result = match cli.command {
-        Commands::Up { backend, fresh } => {
-            commands::up::execute(backend, fresh).await
+        Commands::Init { dir, force } => {
+            commands::init::execute(dir, force).await
+        }
+        Commands::Up { backend, fresh, timeout, verbose } => {
+            commands::up::execute(backend, fresh, timeout, verbose, cli.name).await
         }
         Commands::Down { purge } => {
-            commands::down::execute(purge).await
+            commands::down::execute(purge, cli.name).await
         }
         Commands::Status => {
-            commands::status::execute().await
+            commands::status::execute(cli.name).await
+        }
+        Commands::Mine { count, until_height, address } => {
+            commands::mine::execute(count, until_height, address, cli.name).await
+        }
+        Commands::Doctor => {
+            commands::doctor::execute(cli.name).await
+        }
+        Commands::Snapshot { action: SnapshotAction::Save { snapshot } } => {
+            commands::snapshot::save(&snapshot, cli.name).await
+        }
+        Commands::Snapshot { action: SnapshotAction::Restore { snapshot } } => {
+            commands::snapshot::restore(&snapshot, cli.name).await
+        }
+        Commands::Logs { services, follow, since, grep, bundle } => {
+            let options = commands::logs::LogsOptions { services, follow, since, grep, bundle };
+            commands::logs::execute(options, cli.name).await
         }
-        Commands::Test => {
-            commands::test::execute().await
+        Commands::Test { filter, tags, list, jobs, fail_fast, scenario, parity, report } => {
+            let options = commands::test::TestOptions {
+                filter,
+                tags,
+                list,
+                jobs,
+                fail_fast,
+                scenarios: scenario,
+                parity,
+                reports: report,
+            };
+            commands::test::execute(options, cli.name).await
         }
     };
     
@@ -0,0 +1,94 @@
+use crate::error::Result;
+use clap::ValueEnum;
+use indicatif::{ProgressBar, ProgressDrawTarget};
+use serde::Serialize;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::OnceLock;
+
+/// Format of what a command writes to stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// A single JSON document; human-readable text goes to stderr
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Mode {
+    format: OutputFormat,
+    quiet: bool,
+}
+
+static MODE: OnceLock<Mode> = OnceLock::new();
+
+fn mode() -> Mode {
+    MODE.get().copied().unwrap_or_default()
+}
+
+/// Set the output mode for the rest of the process. Called once from `main`.
+pub fn init(format: OutputFormat, quiet: bool) {
+    let _ = MODE.set(Mode { format, quiet });
+}
+
+pub fn is_json() -> bool {
+    mode().format == OutputFormat::Json
+}
+
+/// Whether human-readable text and progress bars are shown at all.
+pub fn is_verbose() -> bool {
+    !mode().quiet
+}
+
+/// Write human-readable text: to stdout in text mode, to stderr when stdout
+/// carries JSON, and nowhere with `--quiet`. Use through `say!`/`say_inline!`.
+pub fn say(args: fmt::Arguments, newline: bool) {
+    if !is_verbose() {
+        return;
+    }
+
+    let end = if newline { "\n" } else { "" };
+    if is_json() {
+        let mut stderr = io::stderr().lock();
+        let _ = write!(stderr, "{}{}", args, end);
+    } else {
+        let mut stdout = io::stdout().lock();
+        let _ = write!(stdout, "{}{}", args, end);
+        let _ = stdout.flush();
+    }
+}
+
+/// Print a command's JSON result to stdout. Does nothing in text mode.
+pub fn emit<T: Serialize>(value: &T) -> Result<()> {
+    if is_json() {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    }
+    Ok(())
+}
+
+/// Hide `pb` with `--quiet`; in JSON mode it stays on stderr.
+pub fn progress(pb: ProgressBar) -> ProgressBar {
+    if !is_verbose() {
+        pb.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    pb
+}
+
+/// `println!` for human-readable output, honouring `--output` and `--quiet`.
+macro_rules! say {
+    () => {
+        $crate::output::say(format_args!(""), true)
+    };
+    ($($arg:tt)*) => {
+        $crate::output::say(format_args!($($arg)*), true)
+    };
+}
+
+/// `print!` for human-readable output, honouring `--output` and `--quiet`.
+macro_rules! say_inline {
+    ($($arg:tt)*) => {
+        $crate::output::say(format_args!($($arg)*), false)
+    };
+}
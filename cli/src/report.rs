@@ -0,0 +1,309 @@
+use crate::error::{Result, ZeckitError};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Outcome of one smoke test.
+#[derive(Debug, Clone, Serialize)]
+pub struct TestResult {
+    pub name: String,
+    pub passed: bool,
+    /// Not run because a dependency failed or `--fail-fast` stopped the
+    /// run; `error` holds the reason.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+    pub duration_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// What a passing test found, e.g. the balances it checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Recent logs of the services involved, captured when the test failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logs: Option<String>,
+}
+
+/// A smoke test run, as printed by `--output json` and written by `--report`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TestReport {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub duration_ms: u128,
+    pub tests: Vec<TestResult>,
+}
+
+impl TestReport {
+    pub fn new(tests: Vec<TestResult>, duration_ms: u128) -> Self {
+        let passed = tests.iter().filter(|test| test.passed).count();
+        let skipped = tests.iter().filter(|test| test.skipped).count();
+
+        Self {
+            passed,
+            failed: tests.len() - passed - skipped,
+            skipped,
+            duration_ms,
+            tests,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Junit,
+    Tap,
+}
+
+/// `--report FORMAT[=PATH]`. Without a path the report goes to stdout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportTarget {
+    pub format: ReportFormat,
+    pub path: Option<PathBuf>,
+}
+
+impl FromStr for ReportTarget {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        let (format, path) = match value.split_once('=') {
+            Some((format, path)) if !path.is_empty() => (format, Some(PathBuf::from(path))),
+            Some(_) => return Err(format!("missing path in '{}'", value)),
+            None => (value, None),
+        };
+
+        let format = match format {
+            "junit" => ReportFormat::Junit,
+            "tap" => ReportFormat::Tap,
+            other => return Err(format!("unknown report format '{}' (expected junit or tap)", other)),
+        };
+
+        Ok(Self { format, path })
+    }
+}
+
+impl ReportTarget {
+    pub fn write(&self, report: &TestReport) -> Result<()> {
+        let contents = match self.format {
+            ReportFormat::Junit => junit(report),
+            ReportFormat::Tap => tap(report),
+        };
+
+        match &self.path {
+            Some(path) => {
+                if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(path, contents).map_err(|e| {
+                    ZeckitError::Config(format!("Could not write report {}: {}", path.display(), e))
+                })
+            }
+            None => {
+                print!("{}", contents);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// JUnit XML with one `<testcase>` per test. Failure messages go in
+/// `<failure>`, skip reasons in `<skipped>`, captured service logs in
+/// `<system-out>`.
+pub fn junit(report: &TestReport) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"zeckit\" tests=\"{}\" failures=\"{}\" time=\"{}\">",
+        report.tests.len(),
+        report.failed,
+        seconds(report.duration_ms)
+    );
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"smoke\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{}\">",
+        report.tests.len(),
+        report.failed,
+        report.skipped,
+        seconds(report.duration_ms)
+    );
+
+    for test in &report.tests {
+        let _ = write!(
+            xml,
+            "    <testcase classname=\"zeckit.smoke\" name=\"{}\" time=\"{}\"",
+            escape_xml(&test.name),
+            seconds(test.duration_ms)
+        );
+
+        if test.passed {
+            xml.push_str("/>\n");
+            continue;
+        }
+
+        xml.push_str(">\n");
+        if test.skipped {
+            let reason = test.error.as_deref().unwrap_or("skipped");
+            let _ = writeln!(xml, "      <skipped message=\"{}\"/>", escape_xml(reason));
+            xml.push_str("    </testcase>\n");
+            continue;
+        }
+
+        let message = test.error.as_deref().unwrap_or("failed");
+        let _ = writeln!(
+            xml,
+            "      <failure message=\"{}\">{}</failure>",
+            escape_xml(message),
+            escape_xml(message)
+        );
+        if let Some(logs) = &test.logs {
+            let _ = writeln!(xml, "      <system-out>{}</system-out>", escape_xml(logs));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// TAP version 13. Skipped tests use the `# SKIP` directive; failures carry
+/// a YAML block with the message, duration and captured service logs.
+pub fn tap(report: &TestReport) -> String {
+    let mut out = format!("TAP version 13\n1..{}\n", report.tests.len());
+
+    for (i, test) in report.tests.iter().enumerate() {
+        if test.skipped {
+            let reason = test.error.as_deref().unwrap_or_default();
+            let _ = writeln!(out, "ok {} - {} # SKIP {}", i + 1, test.name, reason);
+            continue;
+        }
+
+        let status = if test.passed { "ok" } else { "not ok" };
+        let _ = writeln!(out, "{} {} - {}", status, i + 1, test.name);
+
+        if test.passed {
+            continue;
+        }
+
+        out.push_str("  ---\n");
+        if let Some(error) = &test.error {
+            let _ = writeln!(out, "  message: {}", yaml_string(error));
+        }
+        let _ = writeln!(out, "  duration_ms: {}", test.duration_ms);
+        if let Some(logs) = &test.logs {
+            out.push_str("  logs: |\n");
+            for line in logs.lines() {
+                let _ = writeln!(out, "    {}", line);
+            }
+        }
+        out.push_str("  ...\n");
+    }
+
+    out
+}
+
+fn seconds(ms: u128) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Not allowed in XML 1.0; container logs may contain them
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn yaml_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> TestReport {
+        TestReport::new(
+            vec![
+                TestResult {
+                    name: "Zebra RPC connectivity".to_string(),
+                    passed: true,
+                    skipped: false,
+                    duration_ms: 1200,
+                    error: None,
+                    detail: None,
+                    logs: None,
+                },
+                TestResult {
+                    name: "Faucet stats endpoint".to_string(),
+                    passed: false,
+                    skipped: false,
+                    duration_ms: 2300,
+                    error: Some("Stats missing <faucet_address>".to_string()),
+                    detail: None,
+                    logs: Some("faucet-1  | \u{1b}[31mERROR\u{1b}[0m sync failed\nfaucet-1  | retrying".to_string()),
+                },
+                TestResult {
+                    name: "Wallet balance and shield".to_string(),
+                    passed: false,
+                    skipped: true,
+                    duration_ms: 0,
+                    error: Some("dependency 'Faucet stats endpoint' did not pass".to_string()),
+                    detail: None,
+                    logs: None,
+                },
+            ],
+            3500,
+        )
+    }
+
+    #[test]
+    fn test_report_targets() {
+        assert_eq!(
+            "junit=out/results.xml".parse(),
+            Ok(ReportTarget {
+                format: ReportFormat::Junit,
+                path: Some(PathBuf::from("out/results.xml")),
+            })
+        );
+        assert_eq!(
+            "tap".parse(),
+            Ok(ReportTarget { format: ReportFormat::Tap, path: None })
+        );
+        assert!("junit=".parse::<ReportTarget>().is_err());
+        assert!("html=report.html".parse::<ReportTarget>().is_err());
+    }
+
+    #[test]
+    fn test_junit() {
+        let xml = junit(&report());
+
+        assert!(xml.contains("<testsuite name=\"smoke\" tests=\"3\" failures=\"1\" errors=\"0\" skipped=\"1\""));
+        assert!(xml.contains("name=\"Zebra RPC connectivity\" time=\"1.200\"/>"));
+        assert!(xml.contains("<failure message=\"Stats missing &lt;faucet_address&gt;\">"));
+        assert!(xml.contains("<system-out>faucet-1  | [31mERROR[0m sync failed\nfaucet-1  | retrying</system-out>"));
+        assert!(!xml.contains('\u{1b}'));
+        assert!(xml.contains("<skipped message=\"dependency &apos;Faucet stats endpoint&apos; did not pass\"/>"));
+    }
+
+    #[test]
+    fn test_tap() {
+        let tap = tap(&report());
+
+        assert!(tap.starts_with("TAP version 13\n1..3\n"));
+        assert!(tap.contains("ok 1 - Zebra RPC connectivity\n"));
+        assert!(tap.contains("not ok 2 - Faucet stats endpoint\n  ---\n"));
+        assert!(tap.contains("  message: \"Stats missing <faucet_address>\"\n"));
+        assert!(tap.contains("  logs: |\n    faucet-1  |"));
+        assert!(tap.contains("  ...\nok 3 - Wallet balance and shield # SKIP dependency"));
+    }
+}
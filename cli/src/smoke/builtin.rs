@@ -0,0 +1,319 @@
+use crate::docker::health::HealthChecker;
+use crate::docker::zingo::{WalletBalance, ZingoCli, running_indexer};
+use crate::error::{Result, ZeckitError};
+use crate::smoke::{Outcome, Registry, SmokeTest, TestContext};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use tokio::time::{sleep, Duration, Instant};
+use zeckit_rpc::ZebraRpcClient;
+
+/// Smallest transparent balance worth shielding, in zatoshis.
+const MIN_SHIELD: u64 = 100_000_000;
+
+/// Add the built-in smoke tests to `registry`.
+pub fn register(registry: &mut Registry) -> Result<()> {
+    registry.register(ZebraRpc)?;
+    registry.register(FaucetHealth)?;
+    registry.register(FaucetStats)?;
+    registry.register(FaucetAddress)?;
+    registry.register(WalletShield)?;
+    Ok(())
+}
+
+struct ZebraRpc;
+
+#[async_trait]
+impl SmokeTest for ZebraRpc {
+    fn name(&self) -> &str {
+        "zebra-rpc"
+    }
+
+    fn description(&self) -> &str {
+        "Zebra RPC connectivity"
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["zebra"]
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    fn services(&self) -> &[&str] {
+        &["zebra"]
+    }
+
+    async fn run(&self, ctx: &TestContext) -> Result<Outcome> {
+        test_zebra_rpc(&ctx.zebra).await?;
+        Ok(Outcome::Passed)
+    }
+}
+
+struct FaucetHealth;
+
+#[async_trait]
+impl SmokeTest for FaucetHealth {
+    fn name(&self) -> &str {
+        "faucet-health"
+    }
+
+    fn description(&self) -> &str {
+        "Faucet health check"
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["faucet"]
+    }
+
+    fn dependencies(&self) -> &[&str] {
+        &["zebra-rpc"]
+    }
+
+    fn services(&self) -> &[&str] {
+        &["faucet", "lightwalletd", "zaino"]
+    }
+
+    async fn run(&self, ctx: &TestContext) -> Result<Outcome> {
+        test_faucet_health(&ctx.checker).await?;
+        Ok(Outcome::Passed)
+    }
+}
+
+struct FaucetStats;
+
+#[async_trait]
+impl SmokeTest for FaucetStats {
+    fn name(&self) -> &str {
+        "faucet-stats"
+    }
+
+    fn description(&self) -> &str {
+        "Faucet stats endpoint"
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["faucet", "api"]
+    }
+
+    fn services(&self) -> &[&str] {
+        &["faucet"]
+    }
+
+    async fn run(&self, ctx: &TestContext) -> Result<Outcome> {
+        test_faucet_stats(&ctx.client, &ctx.settings.faucet_api_url).await?;
+        Ok(Outcome::Passed)
+    }
+}
+
+struct FaucetAddress;
+
+#[async_trait]
+impl SmokeTest for FaucetAddress {
+    fn name(&self) -> &str {
+        "faucet-address"
+    }
+
+    fn description(&self) -> &str {
+        "Faucet address retrieval"
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["faucet", "api"]
+    }
+
+    fn services(&self) -> &[&str] {
+        &["faucet"]
+    }
+
+    async fn run(&self, ctx: &TestContext) -> Result<Outcome> {
+        test_faucet_address(&ctx.client, &ctx.settings.faucet_api_url).await?;
+        Ok(Outcome::Passed)
+    }
+}
+
+struct WalletShield;
+
+#[async_trait]
+impl SmokeTest for WalletShield {
+    fn name(&self) -> &str {
+        "wallet-shield"
+    }
+
+    fn description(&self) -> &str {
+        "Wallet balance and shield"
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["wallet", "slow"]
+    }
+
+    fn dependencies(&self) -> &[&str] {
+        &["zebra-rpc"]
+    }
+
+    fn timeout(&self) -> Duration {
+        // Waits up to 3 minutes for a balance, then for the shield to confirm
+        Duration::from_secs(300)
+    }
+
+    fn services(&self) -> &[&str] {
+        &["zingo-wallet", "lightwalletd", "zaino"]
+    }
+
+    async fn run(&self, ctx: &TestContext) -> Result<Outcome> {
+        test_wallet_shield(ctx).await
+    }
+}
+
+async fn test_zebra_rpc(zebra: &ZebraRpcClient) -> Result<()> {
+    zebra.get_block_count().await?;
+    Ok(())
+}
+
+/// Readiness checks the faucet can pass today. Its balance check is
+/// advisory until the faucet can read its wallet balance.
+const FAUCET_READY_CHECKS: [&str; 3] = ["zebra", "backend", "wallet_sync"];
+
+async fn test_faucet_health(checker: &HealthChecker) -> Result<()> {
+    let readiness = checker.faucet_readiness().await?;
+
+    let failing: Vec<&str> = FAUCET_READY_CHECKS
+        .into_iter()
+        .filter(|name| !readiness.passes(name))
+        .collect();
+    if !failing.is_empty() {
+        return Err(ZeckitError::HealthCheck(format!(
+            "Faucet not ready: {} failing ({})",
+            failing.join(", "),
+            readiness.failures().join("; ")
+        )));
+    }
+
+    Ok(())
+}
+
+async fn test_faucet_stats(client: &Client, faucet_url: &str) -> Result<()> {
+    let resp = client
+        .get(format!("{}/stats", faucet_url))
+        .send()
+        .await?;
+
+    if !resp.status().is_success() {
+        return Err(ZeckitError::HealthCheck(
+            "Faucet stats not available".into()
+        ));
+    }
+
+    let json: Value = resp.json().await?;
+    
+    // Verify key fields exist
+    if json.get("faucet_address").is_none() {
+        return Err(ZeckitError::HealthCheck(
+            "Stats missing faucet_address".into()
+        ));
+    }
+    
+    if json.get("current_balance").is_none() {
+        return Err(ZeckitError::HealthCheck(
+            "Stats missing current_balance".into()
+        ));
+    }
+
+    Ok(())
+}
+
+async fn test_faucet_address(client: &Client, faucet_url: &str) -> Result<()> {
+    let resp = client
+        .get(format!("{}/address", faucet_url))
+        .send()
+        .await?;
+
+    if !resp.status().is_success() {
+        return Err(ZeckitError::HealthCheck(
+            "Could not get faucet address".into()
+        ));
+    }
+
+    let json: Value = resp.json().await?;
+    if json.get("address").is_none() {
+        return Err(ZeckitError::HealthCheck(
+            "Invalid address response".into()
+        ));
+    }
+
+    Ok(())
+}
+
+async fn test_wallet_shield(ctx: &TestContext) -> Result<Outcome> {
+    let backend = running_indexer(&ctx.config)?;
+    let wallet = ZingoCli::devnet(&ctx.config, backend);
+
+    wallet.sync().await?;
+    let before = wait_for_wallet_balance(&wallet).await?;
+
+    if before.transparent >= MIN_SHIELD {
+        let txid = match wallet.shield().await {
+            Ok(txid) => txid,
+            // Known upstream bug with large UTXO sets
+            Err(e) if e.to_string().contains("additional change output") => {
+                return Ok(Outcome::Skipped(format!(
+                    "shielding {} ZEC hit the zingolib large UTXO set bug",
+                    zec(before.transparent)
+                )));
+            }
+            Err(e) => return Err(e),
+        };
+
+        ctx.confirm(&txid, 1).await?;
+        wallet.sync().await?;
+
+        let after = wallet.balance().await?;
+        if after.orchard <= before.orchard {
+            return Err(ZeckitError::HealthCheck(format!(
+                "Shield {} confirmed but Orchard balance did not grow",
+                txid
+            )));
+        }
+        Ok(Outcome::PassedWith(format!(
+            "shielded in {}: transparent {} -> {} ZEC, Orchard {} -> {} ZEC",
+            &txid[..16.min(txid.len())],
+            zec(before.transparent),
+            zec(after.transparent),
+            zec(before.orchard),
+            zec(after.orchard)
+        )))
+    } else if before.orchard >= MIN_SHIELD {
+        Ok(Outcome::PassedWith(format!("{} ZEC already shielded in Orchard", zec(before.orchard))))
+    } else if before.transparent > 0 {
+        Ok(Outcome::Skipped(format!(
+            "{} ZEC transparent is too small to shield, need at least {}",
+            zec(before.transparent),
+            zec(MIN_SHIELD)
+        )))
+    } else {
+        Ok(Outcome::Skipped("wallet has no balance yet, mine more blocks".to_string()))
+    }
+}
+
+/// Wait for the wallet to show a balance, re-syncing while it has none.
+/// Returns an empty balance if none shows up within 3 minutes.
+async fn wait_for_wallet_balance(wallet: &ZingoCli) -> Result<WalletBalance> {
+    let start = Instant::now();
+    let timeout = Duration::from_secs(180);
+
+    loop {
+        let balance = wallet.balance().await?;
+        if balance != WalletBalance::default() || start.elapsed() >= timeout {
+            return Ok(balance);
+        }
+
+        sleep(Duration::from_secs(5)).await;
+        wallet.sync().await?;
+    }
+}
+
+fn zec(zatoshis: u64) -> f64 {
+    zatoshis as f64 / 100_000_000.0
+}
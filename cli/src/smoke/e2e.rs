@@ -0,0 +1,217 @@
+//! End-to-end payment tests: funds travel from the faucet (or a test
+//! wallet) to a fresh zingo-cli wallet, are mined, and are checked after the
+//! recipient syncs.
+
+use crate::docker::zingo::{WalletBalance, ZingoCli};
+use crate::error::{Result, ZeckitError};
+use crate::smoke::{Outcome, Registry, SmokeTest, TestContext};
+use async_trait::async_trait;
+use tokio::time::Duration;
+
+/// Amount of each faucet payout, in ZEC.
+const PAYOUT: f64 = 1.0;
+
+/// Amount sent between the two test wallets, in zatoshis.
+const TRANSFER: u64 = 50_000_000;
+
+/// Blocks mined on top of a payout before the recipient spends it.
+const SPEND_CONFIRMATIONS: u32 = 3;
+
+pub fn register(registry: &mut Registry) -> Result<()> {
+    registry.register(Payout { pool: Pool::Orchard })?;
+    registry.register(Payout { pool: Pool::Sapling })?;
+    registry.register(Payout { pool: Pool::Transparent })?;
+    registry.register(OrchardTransfer)?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Pool {
+    Orchard,
+    Sapling,
+    Transparent,
+}
+
+impl Pool {
+    fn name(&self) -> &'static str {
+        match self {
+            Pool::Orchard => "orchard",
+            Pool::Sapling => "sapling",
+            Pool::Transparent => "transparent",
+        }
+    }
+
+    fn of(&self, balance: &WalletBalance) -> u64 {
+        match self {
+            Pool::Orchard => balance.orchard,
+            Pool::Sapling => balance.sapling,
+            Pool::Transparent => balance.transparent,
+        }
+    }
+
+    /// `wallet`'s receiver for this pool: the unified address for Orchard,
+    /// the bare receiver otherwise.
+    async fn address(&self, wallet: &ZingoCli) -> Result<String> {
+        let address = wallet.address().await?;
+        let receiver = match self {
+            Pool::Orchard => address.receivers.orchard_exists.then_some(address.address),
+            Pool::Sapling => address.receivers.sapling,
+            Pool::Transparent => address.receivers.transparent,
+        };
+
+        receiver.ok_or_else(|| {
+            ZeckitError::HealthCheck(format!("Test wallet has no {} receiver", self.name()))
+        })
+    }
+
+    /// Transparent outputs carry no memo.
+    fn memo(&self) -> Option<String> {
+        match self {
+            Pool::Transparent => None,
+            pool => Some(format!("zeckit e2e {} payout", pool.name())),
+        }
+    }
+}
+
+/// A faucet payout to one receiver type of a fresh wallet.
+struct Payout {
+    pool: Pool,
+}
+
+#[async_trait]
+impl SmokeTest for Payout {
+    fn name(&self) -> &str {
+        match self.pool {
+            Pool::Orchard => "payout-orchard",
+            Pool::Sapling => "payout-sapling",
+            Pool::Transparent => "payout-transparent",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self.pool {
+            Pool::Orchard => "Faucet payout to an Orchard receiver",
+            Pool::Sapling => "Faucet payout to a Sapling receiver",
+            Pool::Transparent => "Faucet payout to a transparent receiver",
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["e2e", "wallet", "slow"]
+    }
+
+    fn dependencies(&self) -> &[&str] {
+        &["faucet-health"]
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(300)
+    }
+
+    fn services(&self) -> &[&str] {
+        &["faucet", "zingo-wallet", "lightwalletd", "zaino"]
+    }
+
+    async fn run(&self, ctx: &TestContext) -> Result<Outcome> {
+        let birthday = ctx.zebra.get_block_count().await?;
+        let recipient = ZingoCli::ephemeral(&ctx.config, self.name(), birthday).await?;
+        let address = self.pool.address(&recipient).await?;
+        let memo = self.pool.memo();
+
+        {
+            let _payouts = ctx.payouts.lock().await;
+            let txid = ctx.request_funds(&address, PAYOUT, memo.as_deref()).await?;
+            ctx.confirm(&txid, 1).await?;
+        }
+
+        recipient.sync().await?;
+        expect_received(&recipient, self.pool, zatoshis(PAYOUT), memo.as_deref()).await?;
+        Ok(Outcome::Passed)
+    }
+}
+
+/// A faucet payout to one fresh wallet, which then sends part of it to a
+/// second fresh wallet, Orchard to Orchard.
+struct OrchardTransfer;
+
+#[async_trait]
+impl SmokeTest for OrchardTransfer {
+    fn name(&self) -> &str {
+        "orchard-transfer"
+    }
+
+    fn description(&self) -> &str {
+        "Orchard to Orchard transfer between two test wallets"
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["e2e", "wallet", "slow"]
+    }
+
+    fn dependencies(&self) -> &[&str] {
+        &["faucet-health"]
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(600)
+    }
+
+    fn services(&self) -> &[&str] {
+        &["faucet", "zingo-wallet", "lightwalletd", "zaino"]
+    }
+
+    async fn run(&self, ctx: &TestContext) -> Result<Outcome> {
+        let birthday = ctx.zebra.get_block_count().await?;
+        let sender = ZingoCli::ephemeral(&ctx.config, "transfer-sender", birthday).await?;
+        let recipient = ZingoCli::ephemeral(&ctx.config, "transfer-recipient", birthday).await?;
+        let sender_address = Pool::Orchard.address(&sender).await?;
+        let recipient_address = Pool::Orchard.address(&recipient).await?;
+
+        {
+            let _payouts = ctx.payouts.lock().await;
+            let txid = ctx.request_funds(&sender_address, PAYOUT, None).await?;
+            ctx.confirm(&txid, SPEND_CONFIRMATIONS).await?;
+        }
+
+        sender.sync().await?;
+        expect_received(&sender, Pool::Orchard, zatoshis(PAYOUT), None).await?;
+
+        let memo = "zeckit e2e orchard transfer";
+        let txid = sender.send(&recipient_address, TRANSFER, Some(memo)).await?;
+        ctx.confirm(&txid, 1).await?;
+
+        recipient.sync().await?;
+        expect_received(&recipient, Pool::Orchard, TRANSFER, Some(memo)).await?;
+        Ok(Outcome::Passed)
+    }
+}
+
+/// Check that `wallet` holds exactly `expected` zatoshis in `pool` and, if
+/// given, has received `memo`.
+async fn expect_received(wallet: &ZingoCli, pool: Pool, expected: u64, memo: Option<&str>) -> Result<()> {
+    let received = pool.of(&wallet.balance().await?);
+    if received != expected {
+        return Err(ZeckitError::HealthCheck(format!(
+            "expected {} zatoshis in {}, wallet has {}",
+            expected,
+            pool.name(),
+            received
+        )));
+    }
+
+    if let Some(memo) = memo {
+        let memos = wallet.memos().await?;
+        if !memos.iter().any(|received| received == memo) {
+            return Err(ZeckitError::HealthCheck(format!(
+                "memo {:?} not received (wallet has {:?})",
+                memo, memos
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn zatoshis(zec: f64) -> u64 {
+    (zec * 100_000_000.0).round() as u64
+}
@@ -0,0 +1,492 @@
+//! Smoke tests run by `zeckit test`. Each check implements [`SmokeTest`] and
+//! is added to a [`Registry`]; the runner resolves dependencies and runs
+//! independent tests in parallel.
+
+pub mod builtin;
+pub mod e2e;
+pub mod parity;
+pub mod scenario;
+
+use crate::commands::mine::mine_blocks;
+use crate::config::project::ProjectConfig;
+use crate::config::settings::Settings;
+use crate::docker::health::HealthChecker;
+use crate::error::{Result, ZeckitError};
+use crate::report::TestResult;
+use async_trait::async_trait;
+use colored::*;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+use tokio::time::{sleep, timeout, Duration, Instant};
+use zeckit_rpc::ZebraRpcClient;
+
+/// Default limit for a single test.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Everything a test needs to reach the devnet.
+pub struct TestContext {
+    pub config: ProjectConfig,
+    pub settings: Settings,
+    pub client: Client,
+    pub zebra: ZebraRpcClient,
+    pub checker: HealthChecker,
+    /// Held while a test spends from the faucet wallet and waits for the
+    /// payout to be mined, so parallel tests don't compete for its notes.
+    pub payouts: Mutex<()>,
+}
+
+impl TestContext {
+    pub fn new(config: ProjectConfig) -> Self {
+        let settings = Settings::from_config(&config);
+
+        Self {
+            client: Client::new(),
+            zebra: ZebraRpcClient::new(&settings.zebra_rpc_url),
+            checker: HealthChecker::new(&settings),
+            payouts: Mutex::new(()),
+            config,
+            settings,
+        }
+    }
+
+    /// Request `amount` ZEC from the faucet's `/request`, returning the txid.
+    pub async fn request_funds(&self, address: &str, amount: f64, memo: Option<&str>) -> Result<String> {
+        let resp = self
+            .client
+            .post(format!("{}/request", self.settings.faucet_api_url))
+            .json(&json!({ "address": address, "amount": amount, "memo": memo }))
+            .send()
+            .await?;
+
+        let status = resp.status();
+        let body: Value = resp.json().await?;
+
+        if !status.is_success() {
+            let error = body.get("error").and_then(Value::as_str).unwrap_or("request failed");
+            return Err(ZeckitError::HealthCheck(format!("Faucet returned {}: {}", status, error)));
+        }
+
+        body.get("txid")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| ZeckitError::HealthCheck("Faucet response has no txid".into()))
+    }
+
+    /// Mine `blocks` blocks and wait until `txid` is in one of them.
+    pub async fn confirm(&self, txid: &str, blocks: u32) -> Result<()> {
+        let timeout = Duration::from_secs(self.config.timeouts.mining);
+        let zebra = ZebraRpcClient::new(&self.settings.zebra_rpc_url).with_timeout(timeout);
+
+        // Wait for the transaction to reach the mempool before mining
+        let start = Instant::now();
+        loop {
+            if self.zebra.get_raw_transaction_verbose(txid).await.is_ok() {
+                break;
+            }
+            if start.elapsed() > Duration::from_secs(30) {
+                return Err(ZeckitError::HealthCheck(format!("{} never reached Zebra", txid)));
+            }
+            sleep(Duration::from_secs(1)).await;
+        }
+
+        let height = zebra.get_block_count().await?;
+        mine_blocks(&zebra, height, blocks, timeout).await?;
+
+        match self.zebra.get_raw_transaction_verbose(txid).await?.mined_height() {
+            Some(_) => Ok(()),
+            None => Err(ZeckitError::HealthCheck(format!("{} was not mined", txid))),
+        }
+    }
+}
+
+#[async_trait]
+pub trait SmokeTest: Send + Sync {
+    /// Unique name, used by `--filter` and `dependencies`.
+    fn name(&self) -> &str;
+
+    fn description(&self) -> &str;
+
+    /// Labels for `--tag` selection.
+    fn tags(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Tests that must pass before this one runs. If one fails, this test
+    /// is skipped.
+    fn dependencies(&self) -> &[&str] {
+        &[]
+    }
+
+    fn timeout(&self) -> Duration {
+        DEFAULT_TIMEOUT
+    }
+
+    /// Prefixes of the compose services whose logs explain a failure, e.g.
+    /// `faucet` for `faucet-lwd`.
+    fn services(&self) -> &[&str] {
+        &[]
+    }
+
+    async fn run(&self, ctx: &TestContext) -> Result<Outcome>;
+}
+
+/// How a test that did not fail ended. Tests run in parallel, so anything
+/// worth reporting goes here rather than to the terminal mid-run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    /// Passed; the details are shown next to the result.
+    PassedWith(String),
+    /// Could not check anything, e.g. the wallet has no funds yet. Reported
+    /// as skipped with this reason.
+    Skipped(String),
+}
+
+/// The available tests, in registration order.
+#[derive(Default)]
+pub struct Registry {
+    tests: Vec<Arc<dyn SmokeTest>>,
+}
+
+impl Registry {
+    /// A registry holding the built-in tests.
+    pub fn builtin() -> Result<Self> {
+        let mut registry = Self::default();
+        builtin::register(&mut registry)?;
+        e2e::register(&mut registry)?;
+        Ok(registry)
+    }
+
+    /// Add a test. Names must be unique.
+    pub fn register(&mut self, test: impl SmokeTest + 'static) -> Result<()> {
+        if self.get(test.name()).is_some() {
+            return Err(ZeckitError::Config(format!(
+                "Smoke test '{}' is registered twice",
+                test.name()
+            )));
+        }
+
+        self.tests.push(Arc::new(test));
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Option<&Arc<dyn SmokeTest>> {
+        self.tests.iter().find(|test| test.name() == name)
+    }
+
+    /// Tests whose name contains `filter` and that carry one of `tags`
+    /// (either criterion is skipped when empty), plus everything they
+    /// depend on. Keeps registration order.
+    pub fn select(&self, filter: Option<&str>, tags: &[String]) -> Result<Vec<Arc<dyn SmokeTest>>> {
+        let mut selected = BTreeSet::new();
+        let mut pending: Vec<&str> = self
+            .tests
+            .iter()
+            .filter(|test| filter.is_none_or(|filter| test.name().contains(filter)))
+            .filter(|test| tags.is_empty() || tags.iter().any(|tag| test.tags().contains(&tag.as_str())))
+            .map(|test| test.name())
+            .collect();
+
+        while let Some(name) = pending.pop() {
+            let test = self.get(name).ok_or_else(|| {
+                ZeckitError::Config(format!("Unknown smoke test dependency '{}'", name))
+            })?;
+            if selected.insert(name) {
+                pending.extend(test.dependencies().iter().copied());
+            }
+        }
+
+        Ok(self
+            .tests
+            .iter()
+            .filter(|test| selected.contains(test.name()))
+            .cloned()
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RunOptions {
+    /// Most tests running at once; 1 runs them one after another.
+    pub jobs: usize,
+    /// Skip remaining tests after the first failure.
+    pub fail_fast: bool,
+}
+
+/// Run `tests` in dependency order. Tests whose dependencies have passed run
+/// together, up to `jobs` at a time. Results keep the order of `tests`.
+pub async fn run(
+    tests: &[Arc<dyn SmokeTest>],
+    ctx: Arc<TestContext>,
+    options: RunOptions,
+) -> Vec<TestResult> {
+    let mut results: HashMap<String, TestResult> = HashMap::new();
+    let mut started: HashSet<String> = HashSet::new();
+    let mut running = JoinSet::new();
+    let mut task_names = HashMap::new();
+    let mut stopped = false;
+
+    loop {
+        // Skip tests that can no longer pass and start those that are ready,
+        // until nothing changes
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for test in tests {
+                let name = test.name();
+                if started.contains(name) {
+                    continue;
+                }
+
+                let failed_dependency = test
+                    .dependencies()
+                    .iter()
+                    .find(|dep| results.get(**dep).is_some_and(|result| !result.passed));
+                let skip = if stopped {
+                    Some("not run: an earlier test failed (--fail-fast)".to_string())
+                } else {
+                    failed_dependency.map(|dep| format!("dependency '{}' did not pass", dep))
+                };
+
+                if let Some(reason) = skip {
+                    let result = skipped(name, reason);
+                    print_result(&result);
+                    started.insert(name.to_string());
+                    results.insert(name.to_string(), result);
+                    changed = true;
+                    continue;
+                }
+
+                let ready = test
+                    .dependencies()
+                    .iter()
+                    .all(|dep| results.get(*dep).is_some_and(|result| result.passed));
+                if ready && running.len() < options.jobs.max(1) {
+                    started.insert(name.to_string());
+                    let task = {
+                        let test = Arc::clone(test);
+                        let ctx = Arc::clone(&ctx);
+                        running.spawn(async move { run_one(test, &ctx).await })
+                    };
+                    task_names.insert(task.id(), name.to_string());
+                    changed = true;
+                }
+            }
+        }
+
+        let Some(joined) = running.join_next_with_id().await else {
+            break;
+        };
+        let result = match joined {
+            Ok((_, result)) => result,
+            Err(e) => TestResult {
+                name: task_names.get(&e.id()).cloned().unwrap_or_default(),
+                passed: false,
+                skipped: false,
+                duration_ms: 0,
+                error: Some(format!("test panicked: {}", e)),
+                detail: None,
+                logs: None,
+            },
+        };
+
+        print_result(&result);
+        stopped |= options.fail_fast && !result.passed;
+        results.insert(result.name.clone(), result);
+    }
+
+    tests
+        .iter()
+        .map(|test| {
+            results
+                .remove(test.name())
+                .unwrap_or_else(|| skipped(test.name(), "not run: dependency cycle".to_string()))
+        })
+        .collect()
+}
+
+async fn run_one(test: Arc<dyn SmokeTest>, ctx: &TestContext) -> TestResult {
+    let start = Instant::now();
+    let outcome = match timeout(test.timeout(), test.run(ctx)).await {
+        Ok(outcome) => outcome,
+        Err(_) => Err(ZeckitError::HealthCheck(format!(
+            "timed out after {}s",
+            test.timeout().as_secs()
+        ))),
+    };
+
+    let mut result = TestResult {
+        name: test.name().to_string(),
+        passed: outcome.is_ok(),
+        skipped: false,
+        duration_ms: start.elapsed().as_millis(),
+        error: None,
+        detail: None,
+        logs: None,
+    };
+    match outcome {
+        Ok(Outcome::Passed) => {}
+        Ok(Outcome::PassedWith(detail)) => result.detail = Some(detail),
+        Ok(Outcome::Skipped(reason)) => {
+            result.passed = false;
+            result.skipped = true;
+            result.error = Some(reason);
+        }
+        Err(e) => result.error = Some(e.to_string()),
+    }
+    result
+}
+
+fn print_result(result: &TestResult) {
+    let message = result.error.as_deref().unwrap_or_default();
+    if result.skipped {
+        say!("  {} {} ({})", "SKIP".yellow(), result.name, message);
+    } else if result.passed {
+        say!("  {} {} ({:.1}s)", "PASS".green(), result.name, result.duration_ms as f64 / 1000.0);
+        if let Some(detail) = &result.detail {
+            say!("      {}", detail.dimmed());
+        }
+    } else {
+        say!("  {} {} - {}", "FAIL".red(), result.name, message);
+    }
+}
+
+fn skipped(name: &str, reason: String) -> TestResult {
+    TestResult {
+        name: name.to_string(),
+        passed: false,
+        skipped: true,
+        duration_ms: 0,
+        error: Some(reason),
+        detail: None,
+        logs: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fake {
+        name: &'static str,
+        tags: &'static [&'static str],
+        dependencies: &'static [&'static str],
+        passes: bool,
+    }
+
+    #[async_trait]
+    impl SmokeTest for Fake {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "fake"
+        }
+
+        fn tags(&self) -> &[&str] {
+            self.tags
+        }
+
+        fn dependencies(&self) -> &[&str] {
+            self.dependencies
+        }
+
+        async fn run(&self, _ctx: &TestContext) -> Result<Outcome> {
+            if self.passes {
+                Ok(Outcome::Passed)
+            } else {
+                Err(ZeckitError::HealthCheck("boom".into()))
+            }
+        }
+    }
+
+    fn registry(failing: &str) -> Registry {
+        let mut registry = Registry::default();
+        for (name, tags, dependencies) in [
+            ("zebra", &["node"][..], &[][..]),
+            ("faucet", &["faucet"][..], &["zebra"][..]),
+            ("wallet", &["wallet"][..], &["faucet"][..]),
+            ("other", &["node"][..], &[][..]),
+        ] {
+            let passes = name != failing;
+            registry.register(Fake { name, tags, dependencies, passes }).unwrap();
+        }
+        registry
+    }
+
+    fn names(tests: &[Arc<dyn SmokeTest>]) -> Vec<&str> {
+        tests.iter().map(|test| test.name()).collect()
+    }
+
+    #[test]
+    fn test_select() {
+        let registry = registry("");
+
+        assert_eq!(names(&registry.select(None, &[]).unwrap()).len(), 4);
+        assert_eq!(names(&registry.select(Some("wall"), &[]).unwrap()), ["zebra", "faucet", "wallet"]);
+        assert_eq!(names(&registry.select(None, &["node".into()]).unwrap()), ["zebra", "other"]);
+        assert!(registry.select(Some("nothing"), &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_register_rejects_duplicates() {
+        let mut registry = registry("");
+        let duplicate = Fake { name: "zebra", tags: &[], dependencies: &[], passes: true };
+        assert!(registry.register(duplicate).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_skips_dependents() {
+        let registry = registry("faucet");
+        let tests = registry.select(None, &[]).unwrap();
+        let ctx = Arc::new(TestContext::new(ProjectConfig::default()));
+
+        let results = run(&tests, Arc::clone(&ctx), RunOptions { jobs: 4, fail_fast: false }).await;
+        let outcome: Vec<_> = results.iter().map(|r| (r.name.as_str(), r.passed, r.skipped)).collect();
+        assert_eq!(
+            outcome,
+            [("zebra", true, false), ("faucet", false, false), ("wallet", false, true), ("other", true, false)]
+        );
+
+        let results = run(&tests, ctx, RunOptions { jobs: 1, fail_fast: true }).await;
+        assert!(results[2].skipped && results[3].skipped);
+    }
+
+    struct Ends(&'static str, Outcome);
+
+    #[async_trait]
+    impl SmokeTest for Ends {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "fake"
+        }
+
+        async fn run(&self, _ctx: &TestContext) -> Result<Outcome> {
+            Ok(self.1.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_outcomes() {
+        let tests: Vec<Arc<dyn SmokeTest>> = vec![
+            Arc::new(Ends("detail", Outcome::PassedWith("1 ZEC shielded".into()))),
+            Arc::new(Ends("skip", Outcome::Skipped("no funds".into()))),
+        ];
+        let ctx = Arc::new(TestContext::new(ProjectConfig::default()));
+
+        let results = run(&tests, ctx, RunOptions { jobs: 2, fail_fast: false }).await;
+        assert!(results[0].passed && !results[0].skipped);
+        assert_eq!(results[0].detail.as_deref(), Some("1 ZEC shielded"));
+        assert!(!results[1].passed && results[1].skipped);
+        assert_eq!(results[1].error.as_deref(), Some("no funds"));
+    }
+}
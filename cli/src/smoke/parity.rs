@@ -0,0 +1,382 @@
+//! `zeckit test --parity`: run lightwalletd and Zaino against the same
+//! Zebra, send both the same `CompactTxStreamer` calls and report where
+//! their replies differ.
+
+use super::{print_result, TestContext};
+use crate::config::backend::Backend;
+use crate::config::zebra::ZebraConfig;
+use crate::docker::compose::DockerCompose;
+use crate::docker::indexer::IndexerClient;
+use crate::error::{Result, ZeckitError};
+use crate::report::TestResult;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::net::TcpListener;
+use tokio::time::{sleep, Duration, Instant};
+
+/// Port both indexers listen on inside their containers.
+const INDEXER_PORT: u16 = 9067;
+
+/// Most recent blocks compared by `GetBlockRange`.
+const MAX_BLOCKS: u64 = 100;
+
+/// Most transactions fetched with `GetTransaction`.
+const MAX_TRANSACTIONS: usize = 50;
+
+/// Divergences kept per check; the rest are only counted.
+const MAX_DIVERGENCES: usize = 20;
+
+/// `zeckit test --parity --output json`.
+#[derive(Debug, Serialize)]
+pub struct ParityReport {
+    /// Zebra's height when the comparison started; block-based checks stop
+    /// there.
+    pub height: u64,
+    pub checks: Vec<ParityCheck>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParityCheck {
+    /// The RPC, e.g. `GetTreeState`.
+    pub name: String,
+    pub ok: bool,
+    /// Total divergences found, including those not listed.
+    pub divergence_count: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub divergences: Vec<Divergence>,
+}
+
+/// A value that differs between the two indexers. `path` is a JSON path
+/// into the reply, e.g. `[3].vtx[0].fee`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Divergence {
+    pub path: String,
+    pub lightwalletd: Value,
+    pub zaino: Value,
+}
+
+struct Indexers {
+    lwd: IndexerClient,
+    zaino: IndexerClient,
+}
+
+/// Compare the indexers, starting whichever isn't running for the duration
+/// of the run.
+pub async fn run(ctx: &TestContext) -> Result<(ParityReport, Vec<TestResult>)> {
+    let compose = DockerCompose::new(&ctx.config)?;
+    let started = start_missing(&compose)?;
+
+    let outcome = compare(ctx, &compose).await;
+
+    if !started.is_empty() {
+        say!("  Stopping {}...", started.join(", "));
+        compose.remove_services(&started)?;
+    }
+
+    outcome
+}
+
+/// Start the indexers that aren't running on a free host port, returning
+/// their services. At least one indexer must already be up.
+fn start_missing(compose: &DockerCompose) -> Result<Vec<&'static str>> {
+    let containers = compose.containers()?;
+    let is_running = |service: &str| {
+        containers
+            .iter()
+            .any(|c| c.service == service && c.state == "running")
+    };
+
+    let missing: Vec<Backend> = Backend::INDEXERS
+        .into_iter()
+        .filter(|backend| !backend.indexer_service().is_some_and(is_running))
+        .collect();
+
+    if missing.len() == Backend::INDEXERS.len() {
+        return Err(ZeckitError::ServiceNotReady(
+            "No indexer is running (start the devnet with `zeckit up`)".into(),
+        ));
+    }
+
+    let mut started = Vec::new();
+    for backend in missing {
+        let (Some(service), Some(port_env)) = (backend.indexer_service(), backend.port_env()) else {
+            continue;
+        };
+
+        let port = TcpListener::bind(("127.0.0.1", 0))?.local_addr()?.port();
+        say!("  Starting {} on port {}...", backend.display_name(), port);
+        compose.clone().with_env(port_env, port.to_string()).up(&[service])?;
+        started.push(service);
+    }
+
+    Ok(started)
+}
+
+async fn compare(ctx: &TestContext, compose: &DockerCompose) -> Result<(ParityReport, Vec<TestResult>)> {
+    let height = ctx.zebra.get_block_count().await?;
+    let timeout = Duration::from_secs(ctx.config.timeouts.indexer);
+    let mut indexers = Indexers {
+        lwd: connect(compose, Backend::Lwd, height, timeout).await?,
+        zaino: connect(compose, Backend::Zaino, height, timeout).await?,
+    };
+
+    let mut checks = Vec::new();
+    let mut results = Vec::new();
+    let mut record = |check: ParityCheck, start: Instant| {
+        let result = TestResult {
+            name: format!("parity: {}", check.name),
+            passed: check.ok,
+            skipped: false,
+            duration_ms: start.elapsed().as_millis(),
+            error: check.divergences.first().map(|first| {
+                format!("{} divergence(s), first at {}", check.divergence_count, first.path)
+            }),
+            detail: None,
+            logs: None,
+        };
+        print_result(&result);
+        for divergence in check.divergences.iter().take(5) {
+            say!(
+                "      {}: lightwalletd {} / zaino {}",
+                divergence.path,
+                abbreviate(&divergence.lightwalletd),
+                abbreviate(&divergence.zaino)
+            );
+        }
+        results.push(result);
+        checks.push(check);
+    };
+
+    // The tip moves if Zebra mines during the run, so retry a mismatch
+    let start = Instant::now();
+    let mut attempts = 0;
+    let check = loop {
+        let check = indexers
+            .check("GetLatestBlock", |indexer| async move { indexer.get_latest_block().await })
+            .await;
+        attempts += 1;
+        if check.ok || attempts == 5 {
+            break check;
+        }
+        sleep(Duration::from_secs(2)).await;
+    };
+    record(check, start);
+
+    let start = Instant::now();
+    let first = height.saturating_sub(MAX_BLOCKS - 1).max(1);
+    let blocks = indexers.lwd.get_block_range(first, height).await.unwrap_or_default();
+    let check = indexers
+        .check("GetBlockRange", |indexer| async move { indexer.get_block_range(first, height).await })
+        .await;
+    record(check, start);
+
+    let start = Instant::now();
+    let check = indexers
+        .check("GetTreeState", |indexer| async move { indexer.get_tree_state(height).await })
+        .await;
+    record(check, start);
+
+    // Transactions of the compared blocks, by display txid
+    let txids: Vec<String> = blocks
+        .iter()
+        .flat_map(|block| &block.vtx)
+        .map(|tx| tx.hash.iter().rev().map(|byte| format!("{:02x}", byte)).collect())
+        .take(MAX_TRANSACTIONS)
+        .collect();
+
+    let start = Instant::now();
+    let check = indexers
+        .check("GetTransaction", |indexer| {
+            let txids = txids.clone();
+            async move {
+                let mut replies = Vec::new();
+                for txid in &txids {
+                    replies.push(indexer.get_transaction(txid).await?);
+                }
+                Ok(replies)
+            }
+        })
+        .await;
+    record(check, start);
+
+    let miner_address = ZebraConfig::load(&ctx.config.zebra_config_path())
+        .ok()
+        .and_then(|zebra| zebra.mining.miner_address);
+    match miner_address {
+        Some(address) => {
+            let start = Instant::now();
+            let addresses = vec![address];
+            let check = indexers
+                .check("GetAddressUtxos", |indexer| {
+                    let addresses = addresses.clone();
+                    async move { indexer.get_address_utxos(&addresses, 0).await }
+                })
+                .await;
+            record(check, start);
+        }
+        None => say!("  GetAddressUtxos not compared: no miner address in the Zebra config"),
+    }
+
+    Ok((ParityReport { height, checks }, results))
+}
+
+/// Connect to `backend`'s host port and wait until it has indexed `height`.
+async fn connect(
+    compose: &DockerCompose,
+    backend: Backend,
+    height: u64,
+    timeout: Duration,
+) -> Result<IndexerClient> {
+    let service = backend.indexer_service().unwrap_or_default();
+    let url = format!("http://127.0.0.1:{}", compose.port(service, INDEXER_PORT)?);
+    let start = Instant::now();
+
+    loop {
+        let tip = match IndexerClient::connect(&url, Duration::from_secs(5)).await {
+            Ok(mut client) => match client.get_latest_block().await {
+                Ok(tip) if tip.height >= height => return Ok(client),
+                Ok(tip) => format!("at block {} of {}", tip.height, height),
+                Err(e) => e.to_string(),
+            },
+            Err(e) => e.to_string(),
+        };
+
+        if start.elapsed() > timeout {
+            return Err(ZeckitError::ServiceNotReady(format!(
+                "{} not ready after {}s: {}",
+                backend.display_name(),
+                timeout.as_secs(),
+                tip
+            )));
+        }
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+impl Indexers {
+    /// Make the same call on both indexers and diff the replies. An error
+    /// counts as a reply, so one indexer failing where the other answers is
+    /// a divergence too.
+    async fn check<'a, F, Fut, T>(&'a mut self, name: &str, call: F) -> ParityCheck
+    where
+        F: Fn(&'a mut IndexerClient) -> Fut,
+        Fut: Future<Output = Result<T>> + 'a,
+        T: Serialize,
+    {
+        let lwd = reply(call(&mut self.lwd).await);
+        let zaino = reply(call(&mut self.zaino).await);
+
+        let mut divergences = Vec::new();
+        let divergence_count = diff("", &lwd, &zaino, &mut divergences);
+
+        ParityCheck {
+            name: name.to_string(),
+            ok: divergence_count == 0,
+            divergence_count,
+            divergences,
+        }
+    }
+}
+
+fn reply<T: Serialize>(reply: Result<T>) -> Value {
+    match reply {
+        Ok(reply) => serde_json::to_value(reply).unwrap_or(Value::Null),
+        Err(e) => json!({ "error": e.to_string() }),
+    }
+}
+
+/// `value` as JSON, cut short for the terminal.
+fn abbreviate(value: &Value) -> String {
+    let text = value.to_string();
+    match text.char_indices().nth(60) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+/// Record where `lwd` and `zaino` differ under `path`, keeping at most
+/// [`MAX_DIVERGENCES`]. Returns how many differences there are in total.
+fn diff(path: &str, lwd: &Value, zaino: &Value, divergences: &mut Vec<Divergence>) -> usize {
+    if lwd == zaino {
+        return 0;
+    }
+
+    match (lwd, zaino) {
+        (Value::Object(lwd), Value::Object(zaino)) => {
+            let keys: BTreeSet<&String> = lwd.keys().chain(zaino.keys()).collect();
+            keys.into_iter()
+                .map(|key| {
+                    let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                    let missing = Value::Null;
+                    diff(&path, lwd.get(key).unwrap_or(&missing), zaino.get(key).unwrap_or(&missing), divergences)
+                })
+                .sum()
+        }
+        (Value::Array(lwd), Value::Array(zaino)) => (0..lwd.len().max(zaino.len()))
+            .map(|i| {
+                let path = format!("{}[{}]", path, i);
+                let missing = Value::Null;
+                diff(&path, lwd.get(i).unwrap_or(&missing), zaino.get(i).unwrap_or(&missing), divergences)
+            })
+            .sum(),
+        _ => {
+            if divergences.len() < MAX_DIVERGENCES {
+                divergences.push(Divergence {
+                    path: path.to_string(),
+                    lightwalletd: lwd.clone(),
+                    zaino: zaino.clone(),
+                });
+            }
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let lwd = json!([
+            { "height": 1, "vtx": [{ "fee": 0, "hash": "aa" }] },
+            { "height": 2, "vtx": [] },
+        ]);
+        let zaino = json!([
+            { "height": 1, "vtx": [{ "fee": 1000, "hash": "aa" }] },
+            { "height": 2, "vtx": [], "extra": true },
+            { "height": 3, "vtx": [] },
+        ]);
+
+        let mut divergences = Vec::new();
+        assert_eq!(diff("", &lwd, &zaino, &mut divergences), 3);
+        assert_eq!(
+            divergences,
+            [
+                Divergence { path: "[0].vtx[0].fee".into(), lightwalletd: json!(0), zaino: json!(1000) },
+                Divergence { path: "[1].extra".into(), lightwalletd: Value::Null, zaino: json!(true) },
+                Divergence {
+                    path: "[2]".into(),
+                    lightwalletd: Value::Null,
+                    zaino: json!({ "height": 3, "vtx": [] }),
+                },
+            ]
+        );
+
+        let mut divergences = Vec::new();
+        assert_eq!(diff("", &lwd, &lwd, &mut divergences), 0);
+        assert!(divergences.is_empty());
+    }
+
+    #[test]
+    fn test_diff_limit() {
+        let lwd = Value::Array((0..30).map(Value::from).collect());
+        let zaino = Value::Array((1..31).map(Value::from).collect());
+
+        let mut divergences = Vec::new();
+        assert_eq!(diff("", &lwd, &zaino, &mut divergences), 30);
+        assert_eq!(divergences.len(), MAX_DIVERGENCES);
+    }
+}
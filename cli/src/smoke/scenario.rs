@@ -0,0 +1,453 @@
+//! Declarative end-to-end scenarios for `zeckit test --scenario`. A scenario
+//! is a YAML or TOML file whose steps run in order against the devnet,
+//! through the faucet API and Zebra RPC.
+
+use super::{print_result, skipped, TestContext};
+use crate::commands::mine::mine_blocks;
+use crate::error::{Result, ZeckitError};
+use crate::report::TestResult;
+use colored::*;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tokio::time::{sleep, Duration, Instant};
+use zeckit_rpc::ZebraRpcClient;
+
+/// How long assertions keep retrying unless the step sets `timeout_secs`.
+const DEFAULT_ASSERT_TIMEOUT: u64 = 120;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Steps are written as `- fund: { ... }` rather than YAML tags.
+    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
+    pub steps: Vec<Step>,
+}
+
+/// One scenario step. Text fields may use `${faucet}` (the faucet's unified
+/// address) and `${last_txid}` (the txid of the latest `fund` step).
+/// Assertions are retried until they hold or `timeout_secs` runs out.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Step {
+    /// Send funds from the faucet wallet, optionally with a memo.
+    #[serde(alias = "send")]
+    Fund {
+        address: String,
+        amount: f64,
+        #[serde(default)]
+        memo: Option<String>,
+    },
+    /// Mine blocks on Zebra.
+    Mine {
+        #[serde(default = "one_block")]
+        blocks: u32,
+    },
+    /// Check the balance of the faucet wallet (`wallet: faucet`) or of a
+    /// transparent address (`address`).
+    AssertBalance {
+        #[serde(default)]
+        wallet: Option<String>,
+        #[serde(default)]
+        address: Option<String>,
+        #[serde(default)]
+        pool: Pool,
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
+    /// Check that the faucet wallet received a note with this memo.
+    AssertMemo {
+        memo: String,
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
+    /// Check that a transaction has been mined (default: `${last_txid}`).
+    AssertConfirmed {
+        #[serde(default)]
+        txid: Option<String>,
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
+}
+
+fn one_block() -> u32 {
+    1
+}
+
+/// Balance checked by `assert_balance` on the faucet wallet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Pool {
+    #[default]
+    Total,
+    Orchard,
+    Transparent,
+}
+
+/// A note listed by the faucet's `/incoming`.
+#[derive(Debug, Deserialize)]
+struct IncomingNote {
+    #[serde(default)]
+    memo: Option<String>,
+}
+
+impl Scenario {
+    /// Read a scenario; `.toml` files are TOML, anything else YAML.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|e| {
+            ZeckitError::Config(format!("Could not read scenario {}: {}", path.display(), e))
+        })?;
+
+        let is_toml = path.extension().is_some_and(|ext| ext == "toml");
+        let scenario: Self = if is_toml {
+            toml::from_str(&contents).map_err(|e| e.to_string())
+        } else {
+            serde_yaml::from_str(&contents).map_err(|e| e.to_string())
+        }
+        .map_err(|e| ZeckitError::Config(format!("Invalid scenario {}: {}", path.display(), e)))?;
+
+        scenario
+            .validate()
+            .map_err(|e| ZeckitError::Config(format!("Invalid scenario {}: {}", path.display(), e)))?;
+        Ok(scenario)
+    }
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        if self.steps.is_empty() {
+            return Err("no steps".into());
+        }
+
+        for (i, step) in self.steps.iter().enumerate() {
+            step.validate().map_err(|e| format!("step {}: {}", i + 1, e))?;
+        }
+        Ok(())
+    }
+
+    /// Run the steps in order, one result per step. Steps after a failure
+    /// are skipped.
+    pub async fn run(&self, ctx: &TestContext) -> Vec<TestResult> {
+        say!("  {}", self.name.bold());
+        if let Some(description) = &self.description {
+            say!("  {}", description.dimmed());
+        }
+
+        let mut vars = HashMap::new();
+        if let Ok(stats) = ctx.checker.faucet_stats().await {
+            if let Some(address) = stats.faucet_address {
+                vars.insert("faucet".to_string(), address);
+            }
+        }
+
+        let mut results = Vec::new();
+        let mut failed = false;
+
+        for (i, step) in self.steps.iter().enumerate() {
+            let name = format!("{} / {}. {}", self.name, i + 1, step.summary());
+
+            let result = if failed {
+                skipped(&name, "an earlier step failed".to_string())
+            } else {
+                let start = Instant::now();
+                let outcome = step.run(ctx, &mut vars).await;
+                failed = outcome.is_err();
+                TestResult {
+                    name,
+                    passed: outcome.is_ok(),
+                    skipped: false,
+                    duration_ms: start.elapsed().as_millis(),
+                    error: outcome.err().map(|e| e.to_string()),
+                    detail: None,
+                    logs: None,
+                }
+            };
+
+            print_result(&result);
+            results.push(result);
+        }
+
+        results
+    }
+}
+
+impl Step {
+    fn validate(&self) -> std::result::Result<(), String> {
+        match self {
+            Step::Fund { amount, .. } if *amount <= 0.0 => Err("amount must be positive".into()),
+            Step::Mine { blocks: 0 } => Err("blocks must be at least 1".into()),
+            Step::AssertBalance { wallet, address, pool, min, max, .. } => {
+                match (wallet.as_deref(), address) {
+                    (Some("faucet"), None) => {}
+                    (Some(other), None) => {
+                        return Err(format!("unknown wallet '{}' (only 'faucet' is supported)", other))
+                    }
+                    (None, Some(_)) if *pool != Pool::Total => {
+                        return Err("pool only applies to wallet: faucet".into())
+                    }
+                    (None, Some(_)) => {}
+                    _ => return Err("set exactly one of wallet or address".into()),
+                }
+                if min.is_none() && max.is_none() {
+                    return Err("set min, max or both".into());
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Short description used in results, e.g. `fund 5 ZEC to ${faucet}`.
+    pub fn summary(&self) -> String {
+        match self {
+            Step::Fund { address, amount, memo } => {
+                let memo = memo.as_ref().map(|memo| format!(" with memo {:?}", memo));
+                format!("fund {} ZEC to {}{}", amount, address, memo.unwrap_or_default())
+            }
+            Step::Mine { blocks } => format!("mine {} block(s)", blocks),
+            Step::AssertBalance { wallet, address, pool, min, max, .. } => {
+                let target = match (wallet, address) {
+                    (Some(wallet), _) if *pool == Pool::Total => format!("{} balance", wallet),
+                    (Some(wallet), _) => format!("{} {:?} balance", wallet, pool).to_lowercase(),
+                    (None, address) => format!("balance of {}", address.as_deref().unwrap_or_default()),
+                };
+                let bounds: Vec<String> = [min.map(|min| format!(">= {}", min)), max.map(|max| format!("<= {}", max))]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                format!("assert {} {}", target, bounds.join(" and "))
+            }
+            Step::AssertMemo { memo, .. } => format!("assert memo {:?} received", memo),
+            Step::AssertConfirmed { txid, .. } => {
+                format!("assert {} confirmed", txid.as_deref().unwrap_or("${last_txid}"))
+            }
+        }
+    }
+
+    async fn run(&self, ctx: &TestContext, vars: &mut HashMap<String, String>) -> Result<()> {
+        match self {
+            Step::Fund { address, amount, memo } => {
+                let address = expand(address, vars)?;
+                let memo = memo.as_deref().map(|memo| expand(memo, vars)).transpose()?;
+                let txid = ctx.request_funds(&address, *amount, memo.as_deref()).await?;
+                vars.insert("last_txid".to_string(), txid);
+                Ok(())
+            }
+            Step::Mine { blocks } => {
+                let timeout = Duration::from_secs(ctx.config.timeouts.mining);
+                let zebra = ZebraRpcClient::new(&ctx.settings.zebra_rpc_url).with_timeout(timeout);
+                let start_height = zebra.get_block_count().await?;
+                mine_blocks(&zebra, start_height, *blocks, timeout).await?;
+                Ok(())
+            }
+            Step::AssertBalance { wallet, address, pool, min, max, timeout_secs } => {
+                let address = address.as_deref().map(|address| expand(address, vars)).transpose()?;
+                eventually(*timeout_secs, || async {
+                    let balance = match &address {
+                        Some(address) => ctx.zebra.get_address_balance(&[address]).await?.zec(),
+                        None => {
+                            let stats = ctx.checker.faucet_stats().await?;
+                            match pool {
+                                Pool::Total => stats.current_balance,
+                                Pool::Orchard => stats.orchard_balance,
+                                Pool::Transparent => stats.transparent_balance,
+                            }
+                        }
+                    };
+                    check_bounds(balance, *min, *max)
+                })
+                .await
+                .map_err(|e| {
+                    let name = wallet.as_deref().or(address.as_deref()).unwrap_or_default();
+                    ZeckitError::HealthCheck(format!("{}: {}", name, e))
+                })
+            }
+            Step::AssertMemo { memo, timeout_secs } => {
+                let memo = expand(memo, vars)?;
+                eventually(*timeout_secs, || async {
+                    let notes = incoming_notes(ctx).await?;
+                    let found = notes
+                        .iter()
+                        .filter_map(|note| note.memo.as_deref())
+                        .any(|received| received.trim_end_matches('\0') == memo);
+                    if found {
+                        Ok(())
+                    } else {
+                        Err(ZeckitError::HealthCheck(format!(
+                            "no note with memo {:?} among {} received",
+                            memo,
+                            notes.len()
+                        )))
+                    }
+                })
+                .await
+            }
+            Step::AssertConfirmed { txid, timeout_secs } => {
+                let txid = expand(txid.as_deref().unwrap_or("${last_txid}"), vars)?;
+                eventually(*timeout_secs, || async {
+                    let tx = ctx.zebra.get_raw_transaction_verbose(&txid).await?;
+                    match tx.mined_height() {
+                        Some(_) => Ok(()),
+                        None => Err(ZeckitError::HealthCheck(format!("{} is not mined yet", txid))),
+                    }
+                })
+                .await
+            }
+        }
+    }
+}
+
+/// Replace `${name}` with its value from `vars`.
+fn expand(text: &str, vars: &HashMap<String, String>) -> Result<String> {
+    let mut expanded = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..].find('}').ok_or_else(|| {
+            ZeckitError::Config(format!("Unterminated variable in '{}'", text))
+        })?;
+        let name = &rest[start + 2..start + end];
+        let value = vars.get(name).ok_or_else(|| {
+            ZeckitError::Config(format!("Variable ${{{}}} is not set", name))
+        })?;
+
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(value);
+        rest = &rest[start + end + 1..];
+    }
+
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn check_bounds(balance: f64, min: Option<f64>, max: Option<f64>) -> Result<()> {
+    if let Some(min) = min.filter(|min| balance < *min) {
+        return Err(ZeckitError::HealthCheck(format!("balance {} ZEC is below {}", balance, min)));
+    }
+    if let Some(max) = max.filter(|max| balance > *max) {
+        return Err(ZeckitError::HealthCheck(format!("balance {} ZEC is above {}", balance, max)));
+    }
+    Ok(())
+}
+
+/// Retry `check` every two seconds until it passes or `timeout_secs` (default
+/// [`DEFAULT_ASSERT_TIMEOUT`]) runs out, returning the last error.
+async fn eventually<F, Fut>(timeout_secs: Option<u64>, mut check: F) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<()>>,
+{
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_ASSERT_TIMEOUT));
+    let start = Instant::now();
+
+    loop {
+        match check().await {
+            Ok(()) => return Ok(()),
+            Err(e) if start.elapsed() >= timeout => {
+                return Err(ZeckitError::HealthCheck(format!(
+                    "{} (after {}s)",
+                    e,
+                    timeout.as_secs()
+                )))
+            }
+            Err(_) => sleep(Duration::from_secs(2)).await,
+        }
+    }
+}
+
+async fn incoming_notes(ctx: &TestContext) -> Result<Vec<IncomingNote>> {
+    let resp = ctx
+        .client
+        .get(format!("{}/incoming?limit=1000", ctx.settings.faucet_api_url))
+        .send()
+        .await?;
+
+    if !resp.status().is_success() {
+        return Err(ZeckitError::HealthCheck(format!(
+            "Faucet incoming notes returned {}",
+            resp.status()
+        )));
+    }
+
+    let mut body: Value = resp.json().await?;
+    Ok(serde_json::from_value(body["notes"].take())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_yaml_and_toml() {
+        let yaml = r#"
+name: memo round trip
+steps:
+  - fund: { address: "${faucet}", amount: 5, memo: "hello" }
+  - mine: { blocks: 1 }
+  - assert_balance: { wallet: faucet, pool: orchard, min: 5 }
+  - assert_memo: { memo: "hello", timeout_secs: 30 }
+  - assert_confirmed: {}
+"#;
+        let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+        scenario.validate().unwrap();
+        assert_eq!(scenario.steps.len(), 5);
+        assert_eq!(scenario.steps[0].summary(), "fund 5 ZEC to ${faucet} with memo \"hello\"");
+        assert_eq!(scenario.steps[2].summary(), "assert faucet orchard balance >= 5");
+        assert_eq!(scenario.steps[4].summary(), "assert ${last_txid} confirmed");
+
+        let toml = r#"
+name = "transparent"
+
+[[steps]]
+send = { address = "tmBsTi2xWTjUdEXnuTceL7fecEQKeWaPDJd", amount = 1.5 }
+
+[[steps]]
+assert_balance = { address = "tmBsTi2xWTjUdEXnuTceL7fecEQKeWaPDJd", min = 1.5, max = 2 }
+"#;
+        let scenario: Scenario = toml::from_str(toml).unwrap();
+        scenario.validate().unwrap();
+        assert!(matches!(scenario.steps[0], Step::Fund { memo: None, .. }));
+    }
+
+    #[test]
+    fn test_example_scenario() {
+        let yaml = include_str!("../../../tests/scenarios/faucet-memo.yaml");
+        let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+        scenario.validate().unwrap();
+    }
+
+    #[test]
+    fn test_validate() {
+        let invalid = [
+            "name: x\nsteps: []",
+            "name: x\nsteps:\n  - mine: { blocks: 0 }",
+            "name: x\nsteps:\n  - assert_balance: { wallet: alice, min: 1 }",
+            "name: x\nsteps:\n  - assert_balance: { wallet: faucet }",
+            "name: x\nsteps:\n  - assert_balance: { address: t1, pool: orchard, min: 1 }",
+        ];
+        for yaml in invalid {
+            let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+            assert!(scenario.validate().is_err(), "{}", yaml);
+        }
+
+        let unknown_field = "name: x\nsteps:\n  - mine: { count: 2 }";
+        assert!(serde_yaml::from_str::<Scenario>(unknown_field).is_err());
+    }
+
+    #[test]
+    fn test_expand() {
+        let vars = HashMap::from([("faucet".to_string(), "uregtest1abc".to_string())]);
+
+        assert_eq!(expand("to ${faucet}!", &vars).unwrap(), "to uregtest1abc!");
+        assert_eq!(expand("plain", &vars).unwrap(), "plain");
+        assert!(expand("${last_txid}", &vars).is_err());
+        assert!(expand("${faucet", &vars).is_err());
+    }
+}
@@ -19,14 +19,6 @@ pub fn check_docker_compose() -> bool {
         .unwrap_or(false)
 }
 
-/// Print a formatted banner
-pub fn print_banner(title: &str) {
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("  {}", title);
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!();
-}
-
 /// Format bytes for display
 pub fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
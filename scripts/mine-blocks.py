@@ -2,6 +2,8 @@
 """
 Mine blocks on Zcash regtest using Zebra's generate RPC method.
 This is the correct method per Zebra RPC documentation.
+
+Superseded by `zeckit mine`, which also falls back to the internal miner.
 """
 import requests
 import sys
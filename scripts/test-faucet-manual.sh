@@ -26,28 +26,28 @@ echo "$response" | jq '.' 2>/dev/null || echo "$response"
 echo ""
 
 # Test 2: Health check
-echo -e "${BLUE}[TEST 2]${NC} GET /health"
-response=$(curl -s $FAUCET_URL/health)
+echo -e "${BLUE}[TEST 2]${NC} GET /health/live"
+response=$(curl -s $FAUCET_URL/health/live)
 echo "$response" | jq '.' 2>/dev/null || echo "$response"
 
-# Check if healthy
-if echo "$response" | jq -e '.status == "healthy"' >/dev/null 2>&1; then
-    echo -e "${GREEN}✓ Faucet is healthy${NC}"
+# Check if alive
+if echo "$response" | jq -e '.status == "alive"' >/dev/null 2>&1; then
+    echo -e "${GREEN}✓ Faucet is alive${NC}"
 else
     echo -e "${YELLOW}⚠ Faucet status: $(echo "$response" | jq -r '.status')${NC}"
 fi
 echo ""
 
 # Test 3: Readiness check
-echo -e "${BLUE}[TEST 3]${NC} GET /ready"
-response=$(curl -s $FAUCET_URL/ready)
+echo -e "${BLUE}[TEST 3]${NC} GET /health/ready"
+response=$(curl -s $FAUCET_URL/health/ready)
 echo "$response" | jq '.' 2>/dev/null || echo "$response"
-echo ""
 
-# Test 4: Liveness check
-echo -e "${BLUE}[TEST 4]${NC} GET /live"
-response=$(curl -s $FAUCET_URL/live)
-echo "$response" | jq '.' 2>/dev/null || echo "$response"
+if echo "$response" | jq -e '.status == "ready"' >/dev/null 2>&1; then
+    echo -e "${GREEN}✓ Faucet is ready${NC}"
+else
+    echo -e "${YELLOW}⚠ Failing checks: $(echo "$response" | jq -r '[.checks | to_entries[] | select(.value.required and (.value.ok | not)) | .key] | join(", ")')${NC}"
+fi
 echo ""
 
 echo "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
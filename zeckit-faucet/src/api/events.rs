@@ -0,0 +1,111 @@
+use axum::{
+    extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use serde::Deserialize;
+use std::convert::Infallible;
+use tokio::sync::broadcast;
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+
+use crate::events::EventEnvelope;
+use crate::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct EventsQuery {
+    /// Comma-separated event names, e.g. `payout-submitted,new-block`
+    types: Option<String>,
+}
+
+/// Server-Sent Events stream of faucet activity.
+pub async fn stream_events(
+    State(state): State<AppState>,
+    Query(params): Query<EventsQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let wanted = params.types.as_deref().map(parse_types);
+
+    let stream = filter_events(state.events.subscribe(), wanted).filter_map(|envelope| {
+        Event::default()
+            .id(envelope.id.to_string())
+            .event(envelope.event.name())
+            .json_data(&envelope)
+            .ok()
+            .map(Ok)
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+fn parse_types(types: &str) -> Vec<String> {
+    types
+        .split(',')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Events from `receiver` whose name is in `wanted`, or all of them.
+fn filter_events(
+    receiver: broadcast::Receiver<EventEnvelope>,
+    wanted: Option<Vec<String>>,
+) -> impl Stream<Item = EventEnvelope> {
+    BroadcastStream::new(receiver).filter_map(move |message| {
+        // Lagged subscribers simply skip the events they missed
+        let envelope = message.ok()?;
+        let name = envelope.event.name();
+
+        match &wanted {
+            Some(wanted) if !wanted.iter().any(|w| w == name) => None,
+            _ => Some(envelope),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EventBus, FaucetEvent};
+
+    #[test]
+    fn test_parse_types() {
+        assert_eq!(
+            parse_types("payout-confirmed, new-block,,"),
+            vec!["payout-confirmed".to_string(), "new-block".to_string()]
+        );
+        assert!(parse_types("").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stream_only_yields_wanted_types() {
+        let bus = EventBus::new(16);
+        let stream = filter_events(bus.subscribe(), Some(parse_types("payout-confirmed")));
+
+        bus.publish(FaucetEvent::NewBlock { height: 10 });
+        bus.publish(FaucetEvent::PayoutConfirmed {
+            txid: "abc".to_string(),
+            address: "uregtest1test123".to_string(),
+            amount: 1.0,
+            height: 10,
+        });
+        drop(bus);
+
+        let events: Vec<EventEnvelope> = stream.collect().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, 1);
+        assert_eq!(events[0].event.name(), "payout-confirmed");
+    }
+
+    #[tokio::test]
+    async fn test_stream_without_filter_yields_everything() {
+        let bus = EventBus::new(16);
+        let stream = filter_events(bus.subscribe(), None);
+
+        bus.publish(FaucetEvent::NewBlock { height: 10 });
+        bus.publish(FaucetEvent::SyncError {
+            error: "timeout".to_string(),
+        });
+        drop(bus);
+
+        let names: Vec<&str> = stream.map(|envelope| envelope.event.name()).collect().await;
+        assert_eq!(names, ["new-block", "sync-error"]);
+    }
+}
@@ -4,6 +4,7 @@ use serde_json::json;
 use zcash_address::ZcashAddress;
 use crate::AppState;
 use crate::error::FaucetError;
+use crate::events::FaucetEvent;
 
 #[derive(Debug, Deserialize)]
 pub struct FaucetRequest {
@@ -60,7 +61,24 @@ pub(crate) async fn request_funds(
     
     // Send transaction
     let mut wallet = state.wallet.write().await;
-    let txid = wallet.send_transaction(&validated_address, amount, payload.memo).await?;
+    let txid = match wallet.send_transaction(&validated_address, amount, payload.memo.clone()).await {
+        Ok(txid) => txid,
+        Err(e) => {
+            state.events.publish(FaucetEvent::PayoutFailed {
+                address: validated_address,
+                amount,
+                error: e.to_string(),
+            });
+            return Err(e);
+        }
+    };
+
+    state.events.publish(FaucetEvent::PayoutSubmitted {
+        txid: txid.clone(),
+        address: validated_address.clone(),
+        amount,
+        memo: payload.memo,
+    });
     
     // Get new balance
     let new_balance = wallet.get_balance().await?;
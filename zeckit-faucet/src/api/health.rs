@@ -1,21 +1,232 @@
-use axum::{Json, extract::State};
+use axum::{Json, extract::State, http::StatusCode};
+use serde::Serialize;
 use serde_json::json;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::Instant;
+use tokio::time::{timeout, Duration};
 
 use crate::AppState;
-use crate::error::FaucetError;
 
-pub async fn health_check(
-    State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, FaucetError> {
-    let wallet = state.wallet.read().await;
-    let balance = wallet.get_balance().await?;
-
-    Ok(Json(json!({
-        "status": "healthy",
-        "wallet_backend": "zingolib",
-        "network": "regtest",
-        "balance": balance.total_zec(),
+/// Upper bound for a single dependency check, so a hung dependency
+/// cannot hang the probe itself.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Serialize)]
+pub struct DependencyCheck {
+    ok: bool,
+    /// Whether a failure makes the faucet not ready. Advisory checks are
+    /// reported but don't change the status.
+    required: bool,
+    message: String,
+    latency_ms: u128,
+}
+
+async fn run_check<F>(check: F) -> DependencyCheck
+where
+    F: Future<Output = Result<String, String>>,
+{
+    let start = Instant::now();
+    let (ok, message) = match timeout(CHECK_TIMEOUT, check).await {
+        Ok(Ok(message)) => (true, message),
+        Ok(Err(message)) => (false, message),
+        Err(_) => (false, format!("timed out after {}s", CHECK_TIMEOUT.as_secs())),
+    };
+
+    DependencyCheck {
+        ok,
+        required: true,
+        message,
+        latency_ms: start.elapsed().as_millis(),
+    }
+}
+
+/// Liveness: the process is up and serving requests.
+pub async fn liveness(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let uptime = chrono::Utc::now() - state.start_time;
+
+    Json(json!({
+        "status": "alive",
+        "uptime_seconds": uptime.num_seconds(),
         "timestamp": chrono::Utc::now().to_rfc3339(),
         "version": "0.3.0"
-    })))
-}
\ No newline at end of file
+    }))
+}
+
+/// Readiness: every dependency needed to pay out is available.
+/// Returns 503 with per-dependency status when any check fails.
+pub async fn readiness(
+    State(state): State<AppState>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let zebra = run_check(async {
+        state
+            .zebra
+            .get_block_count()
+            .await
+            .map(|height| format!("height {}", height))
+            .map_err(|e| e.to_string())
+    });
+
+    let backend = run_check(async {
+        let uri = state
+            .config
+            .lightwalletd_uri
+            .parse()
+            .map_err(|e| format!("invalid backend URI: {}", e))?;
+        let info = zingolib::grpc_connector::get_info(uri).await?;
+        Ok(format!(
+            "{} {} at height {}",
+            info.vendor, info.version, info.block_height
+        ))
+    });
+
+    let wallet_sync = run_check(async {
+        let chain_height = state
+            .zebra
+            .get_block_count()
+            .await
+            .map_err(|e| e.to_string())? as u32;
+        let wallet_height = state
+            .wallet
+            .read()
+            .await
+            .get_wallet_height()
+            .await
+            .ok_or_else(|| "wallet has not synced any blocks".to_string())?;
+
+        let lag = chain_height.saturating_sub(wallet_height);
+        let message = format!(
+            "wallet at {} of {} ({} blocks behind)",
+            wallet_height, chain_height, lag
+        );
+        if lag > state.config.ready_max_sync_lag {
+            Err(message)
+        } else {
+            Ok(message)
+        }
+    });
+
+    let balance = run_check(async {
+        let balance = state
+            .wallet
+            .read()
+            .await
+            .get_balance()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // Payouts are sent from the Orchard pool
+        if balance.orchard == 0 {
+            Err(format!(
+                "no spendable Orchard funds (total {} ZEC)",
+                balance.total_zec()
+            ))
+        } else {
+            Ok(format!("{} ZEC spendable", balance.orchard_zec()))
+        }
+    });
+
+    let (zebra, backend, wallet_sync, balance) = tokio::join!(zebra, backend, wallet_sync, balance);
+
+    // get_balance() is not implemented for this zingolib version yet, so the
+    // balance is reported without gating readiness
+    let balance = DependencyCheck { required: false, ..balance };
+
+    readiness_response(BTreeMap::from([
+        ("zebra", zebra),
+        ("backend", backend),
+        ("wallet_sync", wallet_sync),
+        ("balance", balance),
+    ]))
+}
+
+/// 200 and `ready` only when every required check passed, otherwise 503
+/// and `not_ready`; the body lists each check either way.
+fn readiness_response(
+    checks: BTreeMap<&'static str, DependencyCheck>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let ready = checks.values().all(|check| check.ok || !check.required);
+
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(json!({
+            "status": if ready { "ready" } else { "not_ready" },
+            "checks": checks,
+            "network": "regtest",
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "version": "0.3.0"
+        })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(ok: bool) -> DependencyCheck {
+        DependencyCheck {
+            ok,
+            required: true,
+            message: if ok { "fine" } else { "broken" }.to_string(),
+            latency_ms: 3,
+        }
+    }
+
+    #[test]
+    fn test_ready_when_every_check_passes() {
+        let (status, Json(body)) = readiness_response(BTreeMap::from([
+            ("zebra", check(true)),
+            ("balance", check(true)),
+        ]));
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ready");
+        assert_eq!(body["checks"]["zebra"]["ok"], true);
+        assert_eq!(body["checks"]["balance"]["message"], "fine");
+        assert_eq!(body["checks"]["balance"]["latency_ms"], 3);
+    }
+
+    #[test]
+    fn test_not_ready_when_any_check_fails() {
+        let (status, Json(body)) = readiness_response(BTreeMap::from([
+            ("zebra", check(true)),
+            ("wallet_sync", check(false)),
+        ]));
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "not_ready");
+        assert_eq!(body["checks"]["zebra"]["ok"], true);
+        assert_eq!(body["checks"]["wallet_sync"]["ok"], false);
+        assert_eq!(body["checks"]["wallet_sync"]["message"], "broken");
+    }
+
+    #[test]
+    fn test_advisory_failures_do_not_block_readiness() {
+        let (status, Json(body)) = readiness_response(BTreeMap::from([
+            ("zebra", check(true)),
+            ("balance", DependencyCheck { required: false, ..check(false) }),
+        ]));
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ready");
+        assert_eq!(body["checks"]["balance"]["ok"], false);
+        assert_eq!(body["checks"]["balance"]["required"], false);
+    }
+
+    #[tokio::test]
+    async fn test_run_check_reports_outcome() {
+        let passed = run_check(async { Ok("height 10".to_string()) }).await;
+        assert!(passed.ok);
+        assert_eq!(passed.message, "height 10");
+
+        let failed = run_check(async { Err("connection refused".to_string()) }).await;
+        assert!(!failed.ok);
+        assert_eq!(failed.message, "connection refused");
+    }
+}
@@ -1,6 +1,8 @@
 pub mod health;
 pub mod faucet;
 pub mod stats;
+pub mod events;
+pub mod webhooks;
 
 use axum::{Json, extract::State};
 use serde_json::json;
@@ -16,10 +18,15 @@ pub async fn root(State(_state): State<AppState>) -> Json<serde_json::Value> {
         "wallet_backend": "zingolib",
         "endpoints": {
             "health": "/health",
+            "liveness": "/health/live",
+            "readiness": "/health/ready",
             "stats": "/stats",
             "request": "/request",
             "address": "/address",
-            "history": "/history"
+            "history": "/history",
+            "incoming": "/incoming",
+            "events": "/events",
+            "webhook_deliveries": "/webhooks/deliveries"
         }
     }))
 }
\ No newline at end of file
@@ -4,12 +4,20 @@ use serde_json::json;
 
 use crate::AppState;
 use crate::error::FaucetError;
+use crate::wallet::{IncomingFilter, Pool};
 
 #[derive(Debug, Deserialize)]
 pub struct HistoryQuery {
     limit: Option<usize>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct IncomingQuery {
+    limit: Option<usize>,
+    pool: Option<String>,
+    min_height: Option<u32>,
+}
+
 pub async fn get_stats(
     State(state): State<AppState>,
 ) -> Result<Json<serde_json::Value>, FaucetError> {
@@ -18,6 +26,7 @@ pub async fn get_stats(
     let address = wallet.get_unified_address().await?;
     let balance = wallet.get_balance().await?;
     let (tx_count, total_sent) = wallet.get_stats();
+    let wallet_height = wallet.get_wallet_height().await;
     
     let uptime = chrono::Utc::now() - state.start_time;
     let uptime_seconds = uptime.num_seconds();
@@ -30,6 +39,7 @@ pub async fn get_stats(
         "current_balance": balance.total_zec(),
         "orchard_balance": balance.orchard_zec(),
         "transparent_balance": balance.transparent_zec(),
+        "wallet_height": wallet_height,
         "total_requests": tx_count,
         "total_sent": total_sent,
         "last_request": last_request,
@@ -55,3 +65,39 @@ pub async fn get_history(
         "transactions": history
     })))
 }
+
+/// Notes received by the faucet wallet, e.g. funds returned by round-trip tests.
+pub async fn get_incoming(
+    State(state): State<AppState>,
+    Query(params): Query<IncomingQuery>,
+) -> Result<Json<serde_json::Value>, FaucetError> {
+    let pool = params
+        .pool
+        .as_deref()
+        .map(str::parse::<Pool>)
+        .transpose()
+        .map_err(FaucetError::Validation)?;
+
+    let filter = IncomingFilter { pool, min_height: params.min_height };
+
+    let wallet = state.wallet.read().await;
+
+    let limit = params.limit.unwrap_or(100).min(1000).max(1);
+    let mut notes: Vec<_> = wallet
+        .get_incoming_notes()
+        .await?
+        .into_iter()
+        .filter(|note| filter.matches(note))
+        .collect();
+
+    // Total over every matching note, not just the returned page
+    let total_received: u64 = notes.iter().map(|note| note.value).sum();
+    notes.truncate(limit);
+
+    Ok(Json(json!({
+        "count": notes.len(),
+        "limit": limit,
+        "total_received": total_received as f64 / 100_000_000.0,
+        "notes": notes
+    })))
+}
@@ -0,0 +1,26 @@
+use axum::{Json, extract::{State, Query}};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveriesQuery {
+    limit: Option<usize>,
+}
+
+/// Recent webhook delivery attempts, newest first.
+pub async fn get_deliveries(
+    State(state): State<AppState>,
+    Query(params): Query<DeliveriesQuery>,
+) -> Json<serde_json::Value> {
+    let limit = params.limit.unwrap_or(100).min(1000).max(1);
+    let deliveries = state.webhook_deliveries.read().await.get_recent(limit);
+
+    Json(json!({
+        "count": deliveries.len(),
+        "limit": limit,
+        "webhook_urls": state.config.webhook_urls.len(),
+        "deliveries": deliveries
+    }))
+}
@@ -9,6 +9,15 @@ pub struct Config {
     pub faucet_amount_min: f64,
     pub faucet_amount_max: f64,
     pub faucet_amount_default: f64,
+    pub monitor_interval_secs: u64,
+    pub ready_max_sync_lag: u32,
+    pub low_balance_threshold: f64,
+    pub webhook_urls: Vec<String>,
+    pub webhook_secret: Option<String>,
+    pub webhook_max_attempts: u32,
+    /// Regtest activation heights of every upgrade in `UPGRADES`, matching
+    /// Zebra's. Empty means zingolib's default of every upgrade at height 1.
+    pub activation_heights: Vec<(String, u32)>,
 }
 
 impl Config {
@@ -33,6 +42,106 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(10.0),
+            monitor_interval_secs: std::env::var("MONITOR_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
+            ready_max_sync_lag: std::env::var("READY_MAX_SYNC_LAG")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(2),
+            low_balance_threshold: std::env::var("LOW_BALANCE_THRESHOLD")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10.0),
+            webhook_urls: std::env::var("WEBHOOK_URLS")
+                .map(|urls| {
+                    urls.split(',')
+                        .map(|url| url.trim().to_string())
+                        .filter(|url| !url.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            webhook_secret: std::env::var("WEBHOOK_SECRET")
+                .ok()
+                .filter(|secret| !secret.is_empty()),
+            webhook_max_attempts: std::env::var("WEBHOOK_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
+            activation_heights: parse_activation_heights(
+                &std::env::var("ACTIVATION_HEIGHTS").unwrap_or_default(),
+            )?,
         })
     }
-}
\ No newline at end of file
+}
+
+/// Network upgrades `ACTIVATION_HEIGHTS` must set, in activation order.
+pub const UPGRADES: [&str; 7] = ["Overwinter", "Sapling", "Blossom", "Heartwood", "Canopy", "NU5", "NU6"];
+
+/// Parse `Name=height` pairs separated by commas, e.g. `Overwinter=1,...,NU6=10`.
+/// Unless empty, every upgrade must be set, so the wallet never falls back to
+/// defaults that may differ from Zebra's.
+pub(crate) fn parse_activation_heights(value: &str) -> anyhow::Result<Vec<(String, u32)>> {
+    let mut heights = Vec::new();
+    for pair in value.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+        let (name, height) = pair
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("invalid ACTIVATION_HEIGHTS entry '{}'", pair))?;
+        let upgrade = UPGRADES
+            .into_iter()
+            .find(|upgrade| upgrade.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| anyhow::anyhow!("unknown upgrade in ACTIVATION_HEIGHTS entry '{}'", pair))?;
+        let height = height
+            .trim()
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid height in ACTIVATION_HEIGHTS entry '{}': {}", pair, e))?;
+        heights.push((upgrade.to_string(), height));
+    }
+
+    if !heights.is_empty() {
+        let missing: Vec<&str> = UPGRADES
+            .into_iter()
+            .filter(|upgrade| !heights.iter().any(|(name, _)| name == upgrade))
+            .collect();
+        if !missing.is_empty() {
+            anyhow::bail!("ACTIVATION_HEIGHTS must set every upgrade (missing {})", missing.join(", "));
+        }
+    }
+
+    Ok(heights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: &str = "Overwinter=1,Sapling=1,Blossom=1,Heartwood=1,Canopy=1,NU5=1,NU6=10";
+
+    #[test]
+    fn test_parse_activation_heights() {
+        assert!(parse_activation_heights("").unwrap().is_empty());
+
+        let heights = parse_activation_heights(ALL).unwrap();
+        assert_eq!(heights.len(), UPGRADES.len());
+        assert_eq!(heights[5], ("NU5".to_string(), 1));
+        assert_eq!(heights[6], ("NU6".to_string(), 10));
+
+        // Names are matched case-insensitively and stored as in `UPGRADES`
+        let heights = parse_activation_heights(&ALL.to_lowercase().replace(',', " , ")).unwrap();
+        assert_eq!(heights[6], ("NU6".to_string(), 10));
+    }
+
+    #[test]
+    fn test_parse_activation_heights_rejects_malformed_entries() {
+        assert!(parse_activation_heights("NU5").is_err());
+        assert!(parse_activation_heights(&ALL.replace("NU6=10", "NU6=soon")).is_err());
+        assert!(parse_activation_heights(&format!("{},NU7=20", ALL)).is_err());
+    }
+
+    #[test]
+    fn test_parse_activation_heights_requires_every_upgrade() {
+        let error = parse_activation_heights("Canopy=1,NU5=1,NU6=10").unwrap_err().to_string();
+        assert!(error.contains("missing Overwinter, Sapling, Blossom, Heartwood"), "{}", error);
+    }
+}
@@ -28,6 +28,9 @@ pub enum FaucetError {
 
     #[error("Internal error: {0}")]
     Internal(String),
+
+    #[error("Zebra RPC error: {0}")]
+    Rpc(#[from] zeckit_rpc::RpcError),
 }
 
 impl IntoResponse for FaucetError {
@@ -40,6 +43,7 @@ impl IntoResponse for FaucetError {
             FaucetError::Wallet(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             FaucetError::TransactionFailed(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             FaucetError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            FaucetError::Rpc(e) => (StatusCode::BAD_GATEWAY, e.to_string()),
         };
 
         let body = Json(json!({
@@ -0,0 +1,161 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::debug;
+
+/// Activity published by the faucet for `/events` subscribers.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum FaucetEvent {
+    PayoutSubmitted {
+        txid: String,
+        address: String,
+        amount: f64,
+        memo: Option<String>,
+    },
+    PayoutConfirmed {
+        txid: String,
+        address: String,
+        amount: f64,
+        height: u32,
+    },
+    SyncProgress {
+        wallet_height: u32,
+        chain_height: u32,
+    },
+    BalanceChanged {
+        previous: f64,
+        current: f64,
+    },
+    NewBlock {
+        height: u32,
+    },
+    PayoutFailed {
+        address: String,
+        amount: f64,
+        error: String,
+    },
+    LowBalance {
+        balance: f64,
+        threshold: f64,
+    },
+    SyncError {
+        error: String,
+    },
+}
+
+impl FaucetEvent {
+    /// Event name used for the SSE `event:` field and for filtering.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FaucetEvent::PayoutSubmitted { .. } => "payout-submitted",
+            FaucetEvent::PayoutConfirmed { .. } => "payout-confirmed",
+            FaucetEvent::SyncProgress { .. } => "sync-progress",
+            FaucetEvent::BalanceChanged { .. } => "balance-changed",
+            FaucetEvent::NewBlock { .. } => "new-block",
+            FaucetEvent::PayoutFailed { .. } => "payout-failed",
+            FaucetEvent::LowBalance { .. } => "low-balance",
+            FaucetEvent::SyncError { .. } => "sync-error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventEnvelope {
+    pub id: u64,
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: FaucetEvent,
+}
+
+/// Fan-out of faucet events to any number of subscribers.
+/// Publishing never blocks; slow subscribers miss events rather than
+/// holding up payouts.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<EventEnvelope>,
+    next_id: std::sync::Arc<std::sync::atomic::AtomicU64>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            sender,
+            next_id: Default::default(),
+        }
+    }
+
+    pub fn publish(&self, event: FaucetEvent) {
+        let id = self
+            .next_id
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        debug!("Publishing event {} ({})", id, event.name());
+
+        // An error only means nobody is listening right now
+        let _ = self.sender.send(EventEnvelope {
+            id,
+            timestamp: Utc::now(),
+            event,
+        });
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<EventEnvelope> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_subscribers_receive_published_events() {
+        let bus = EventBus::new(16);
+        let mut first = bus.subscribe();
+        let mut second = bus.subscribe();
+
+        bus.publish(FaucetEvent::NewBlock { height: 7 });
+        bus.publish(FaucetEvent::PayoutConfirmed {
+            txid: "abc".to_string(),
+            address: "uregtest1test123".to_string(),
+            amount: 1.0,
+            height: 8,
+        });
+
+        for receiver in [&mut first, &mut second] {
+            let envelope = receiver.recv().await.unwrap();
+            assert_eq!(envelope.id, 0);
+            assert!(matches!(envelope.event, FaucetEvent::NewBlock { height: 7 }));
+
+            let envelope = receiver.recv().await.unwrap();
+            assert_eq!(envelope.id, 1);
+            assert_eq!(envelope.event.name(), "payout-confirmed");
+        }
+    }
+
+    #[test]
+    fn test_publish_without_subscribers_is_fine() {
+        let bus = EventBus::new(16);
+        bus.publish(FaucetEvent::NewBlock { height: 1 });
+
+        // Late subscribers only see later events
+        let mut late = bus.subscribe();
+        assert!(late.try_recv().is_err());
+        bus.publish(FaucetEvent::NewBlock { height: 2 });
+        assert_eq!(late.try_recv().unwrap().id, 1);
+    }
+
+    #[test]
+    fn test_envelope_serializes_flat_with_type() {
+        let envelope = EventEnvelope {
+            id: 3,
+            timestamp: Utc::now(),
+            event: FaucetEvent::NewBlock { height: 12 },
+        };
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(json["id"], 3);
+        assert_eq!(json["type"], "new-block");
+        assert_eq!(json["height"], 12);
+    }
+}
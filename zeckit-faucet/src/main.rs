@@ -14,14 +14,23 @@ mod wallet;
 mod api;
 mod validation;
 mod error;
+mod events;
+mod monitor;
+mod webhooks;
 
 use config::Config;
+use events::EventBus;
 use wallet::WalletManager;
+use webhooks::{DeliveryLog, WebhookDispatcher};
+use zeckit_rpc::ZebraRpcClient;
 
 #[derive(Clone)]
 pub struct AppState {
     pub wallet: Arc<RwLock<WalletManager>>,
     pub config: Arc<Config>,
+    pub zebra: ZebraRpcClient,
+    pub events: EventBus,
+    pub webhook_deliveries: Arc<RwLock<DeliveryLog>>,
     pub start_time: chrono::DateTime<chrono::Utc>,
 }
 
@@ -45,11 +54,30 @@ async fn main() -> anyhow::Result<()> {
     info!("  LightwalletD URI: {}", config.lightwalletd_uri);
     info!("  Data dir: {}", config.zingo_data_dir.display());
 
+    let zebra = ZebraRpcClient::new(&config.zebra_rpc_url);
+
+    // The wallet must use the same upgrade heights as the chain it scans
+    if !config.activation_heights.is_empty() {
+        let info = zebra.get_blockchain_info().await?;
+        let mismatches = info.activation_height_mismatches(
+            config
+                .activation_heights
+                .iter()
+                .map(|(name, height)| (name.as_str(), *height)),
+        );
+        if !mismatches.is_empty() {
+            error!("Activation heights don't match Zebra: {}", mismatches.join("; "));
+            anyhow::bail!("activation heights don't match Zebra: {}", mismatches.join("; "));
+        }
+        info!("  Activation heights: {:?}", config.activation_heights);
+    }
+
     // Initialize wallet manager
     info!("💼 Initializing wallet...");
     let wallet = WalletManager::new(
         config.zingo_data_dir.clone(),
         config.lightwalletd_uri.clone(),
+        &config.activation_heights,
     ).await?;
 
     let wallet = Arc::new(RwLock::new(wallet));
@@ -65,19 +93,37 @@ async fn main() -> anyhow::Result<()> {
         info!("  Balance: {} ZEC", balance.total_zec());
     }
 
+    let webhook_deliveries = Arc::new(RwLock::new(DeliveryLog::load(&config.zingo_data_dir)?));
+
     // Build application state
     let state = AppState {
         wallet,
         config: Arc::new(config.clone()),
+        zebra,
+        events: EventBus::new(256),
+        webhook_deliveries: webhook_deliveries.clone(),
         start_time: chrono::Utc::now(),
     };
 
+    if !config.webhook_urls.is_empty() {
+        let dispatcher = WebhookDispatcher::from_config(&config, webhook_deliveries);
+        tokio::spawn(dispatcher.run(state.events.subscribe()));
+    }
+
+    // Watch chain and wallet for /events subscribers
+    tokio::spawn(monitor::run(state.clone()));
+
     // Build router
     let app = Router::new()
         .route("/", get(api::root))
-        .route("/health", get(api::health::health_check))
+        .route("/health", get(api::health::liveness))
+        .route("/health/live", get(api::health::liveness))
+        .route("/health/ready", get(api::health::readiness))
         .route("/stats", get(api::stats::get_stats))
         .route("/history", get(api::stats::get_history))
+        .route("/incoming", get(api::stats::get_incoming))
+        .route("/events", get(api::events::stream_events))
+        .route("/webhooks/deliveries", get(api::webhooks::get_deliveries))
         .route("/request", post(api::faucet::request_funds))
         .route("/address", get(api::faucet::get_faucet_address))
         .layer(CorsLayer::permissive())
@@ -0,0 +1,112 @@
+use crate::events::FaucetEvent;
+use crate::AppState;
+use std::collections::HashMap;
+use tokio::sync::broadcast::error::TryRecvError;
+use tokio::time::{interval, Duration};
+use tracing::{debug, warn};
+
+/// Background task that watches Zebra and the faucet wallet and publishes
+/// new-block, sync-progress, balance-changed, payout-confirmed, low-balance
+/// and sync-error events.
+pub async fn run(state: AppState) {
+    let mut ticker = interval(Duration::from_secs(state.config.monitor_interval_secs.max(1)));
+    let mut events = state.events.subscribe();
+    // Submitted payouts not yet mined, by txid, with their address and amount
+    let mut pending_payouts: HashMap<String, (String, f64)> = HashMap::new();
+    let mut last_height: Option<u32> = None;
+    let mut last_balance: Option<f64> = None;
+    let mut low_balance_reported = false;
+
+    loop {
+        ticker.tick().await;
+
+        // Pick up payouts submitted since the last tick
+        loop {
+            match events.try_recv() {
+                Ok(envelope) => {
+                    if let FaucetEvent::PayoutSubmitted { txid, address, amount, .. } = envelope.event {
+                        pending_payouts.insert(txid, (address, amount));
+                    }
+                }
+                Err(TryRecvError::Lagged(skipped)) => {
+                    warn!("Chain monitor missed {} events", skipped);
+                }
+                Err(_) => break,
+            }
+        }
+
+        let chain_height = match state.zebra.get_block_count().await {
+            Ok(height) => height as u32,
+            Err(e) => {
+                debug!("Chain monitor could not reach Zebra: {}", e);
+                continue;
+            }
+        };
+
+        if last_height.map_or(true, |last| chain_height > last) {
+            if last_height.is_some() {
+                state.events.publish(FaucetEvent::NewBlock { height: chain_height });
+            }
+            last_height = Some(chain_height);
+
+            // Sync outside the faucet's wallet lock so requests and health
+            // probes aren't held up for the whole sync
+            let syncer = state.wallet.read().await.syncer();
+            if let Err(e) = syncer.sync().await {
+                warn!("Wallet sync failed: {}", e);
+                state.events.publish(FaucetEvent::SyncError {
+                    error: e.to_string(),
+                });
+            }
+            if let Some(wallet_height) = state.wallet.read().await.get_wallet_height().await {
+                state.events.publish(FaucetEvent::SyncProgress {
+                    wallet_height,
+                    chain_height,
+                });
+            }
+        }
+
+        match state.wallet.read().await.get_balance().await {
+            Ok(balance) => {
+                let current = balance.total_zec();
+                if let Some(previous) = last_balance.filter(|previous| *previous != current) {
+                    state.events.publish(FaucetEvent::BalanceChanged { previous, current });
+                }
+                last_balance = Some(current);
+
+                // Report once per crossing below the threshold
+                let threshold = state.config.low_balance_threshold;
+                if current < threshold && !low_balance_reported {
+                    state.events.publish(FaucetEvent::LowBalance {
+                        balance: current,
+                        threshold,
+                    });
+                }
+                low_balance_reported = current < threshold;
+            }
+            Err(e) => debug!("Chain monitor could not read balance: {}", e),
+        }
+
+        let mut confirmed = Vec::new();
+        for txid in pending_payouts.keys() {
+            match state.zebra.get_raw_transaction_verbose(txid).await {
+                Ok(tx) => {
+                    if let Some(height) = tx.mined_height() {
+                        confirmed.push((txid.clone(), height));
+                    }
+                }
+                Err(e) => debug!("Could not look up payout {}: {}", txid, e),
+            }
+        }
+        for (txid, height) in confirmed {
+            if let Some((address, amount)) = pending_payouts.remove(&txid) {
+                state.events.publish(FaucetEvent::PayoutConfirmed {
+                    txid,
+                    address,
+                    amount,
+                    height,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Pool {
+    Transparent,
+    Sapling,
+    Orchard,
+}
+
+impl std::str::FromStr for Pool {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "transparent" => Ok(Pool::Transparent),
+            "sapling" => Ok(Pool::Sapling),
+            "orchard" => Ok(Pool::Orchard),
+            other => Err(format!(
+                "Unknown pool '{}'. Use 'transparent', 'sapling' or 'orchard'",
+                other
+            )),
+        }
+    }
+}
+
+/// A note (or transparent coin) received by the faucet wallet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncomingNote {
+    pub txid: String,
+    pub pool: Pool,
+    pub value: u64,
+    pub value_zec: f64,
+    /// `None` while the transaction is still in the mempool.
+    pub height: Option<u32>,
+    /// Decrypted memo text. Transparent coins never carry a memo.
+    pub memo: Option<String>,
+    pub is_coinbase: bool,
+}
+
+impl IncomingNote {
+    pub fn new(
+        txid: String,
+        pool: Pool,
+        value: u64,
+        height: Option<u32>,
+        memo: Option<String>,
+        is_coinbase: bool,
+    ) -> Self {
+        Self {
+            txid,
+            pool,
+            value,
+            value_zec: value as f64 / 100_000_000.0,
+            height,
+            memo,
+            is_coinbase,
+        }
+    }
+}
+
+/// Which notes `GET /incoming` lists. Notes still in the mempool always
+/// pass `min_height`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IncomingFilter {
+    pub pool: Option<Pool>,
+    pub min_height: Option<u32>,
+}
+
+impl IncomingFilter {
+    pub fn matches(&self, note: &IncomingNote) -> bool {
+        let pool_matches = self.pool.map_or(true, |pool| note.pool == pool);
+        let height_matches = match (self.min_height, note.height) {
+            (Some(min), Some(height)) => height >= min,
+            _ => true,
+        };
+        pool_matches && height_matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(pool: Pool, value: u64, height: Option<u32>) -> IncomingNote {
+        IncomingNote::new("txid".to_string(), pool, value, height, None, false)
+    }
+
+    #[test]
+    fn test_pool_from_str() {
+        assert_eq!("transparent".parse::<Pool>(), Ok(Pool::Transparent));
+        assert_eq!("Sapling".parse::<Pool>(), Ok(Pool::Sapling));
+        assert_eq!("ORCHARD".parse::<Pool>(), Ok(Pool::Orchard));
+        assert!("sprout".parse::<Pool>().is_err());
+    }
+
+    #[test]
+    fn test_filter_by_pool_and_min_height() {
+        let orchard_old = note(Pool::Orchard, 1, Some(10));
+        let orchard_new = note(Pool::Orchard, 2, Some(20));
+        let orchard_pending = note(Pool::Orchard, 3, None);
+        let sapling_new = note(Pool::Sapling, 4, Some(20));
+
+        let everything = IncomingFilter::default();
+        assert!(everything.matches(&orchard_old));
+        assert!(everything.matches(&sapling_new));
+
+        let filter = IncomingFilter { pool: Some(Pool::Orchard), min_height: Some(15) };
+        assert!(!filter.matches(&orchard_old));
+        assert!(filter.matches(&orchard_new));
+        assert!(filter.matches(&orchard_pending));
+        assert!(!filter.matches(&sapling_new));
+    }
+
+    #[test]
+    fn test_value_zec() {
+        assert_eq!(note(Pool::Transparent, 250_000_000, None).value_zec, 2.5);
+    }
+}
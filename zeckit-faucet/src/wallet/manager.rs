@@ -1,10 +1,16 @@
 use crate::error::FaucetError;
 use crate::wallet::history::{TransactionHistory, TransactionRecord};
+use crate::wallet::incoming::{IncomingNote, Pool};
+use std::collections::HashSet;
 use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
 use tracing::info;
+use zcash_primitives::memo::Memo;
 use zingolib::{
     lightclient::LightClient,
-    config::ZingoConfig,
+    config::{RegtestNetwork, ZingoConfig},
+    wallet::LightWallet,
 };
 // Import from axum instead of separate http crate
 use axum::http::Uri;
@@ -35,14 +41,33 @@ impl Balance {
 }
 
 pub struct WalletManager {
-    client: LightClient,
+    client: Arc<Mutex<LightClient>>,
+    /// The client's wallet, readable while a sync holds `client`.
+    wallet: Arc<RwLock<LightWallet>>,
     history: TransactionHistory,
 }
 
+/// Syncs the wallet without borrowing the `WalletManager`, so a long sync
+/// doesn't hold the faucet's wallet lock and block `/request` or `/health`.
+#[derive(Clone)]
+pub struct WalletSyncer {
+    client: Arc<Mutex<LightClient>>,
+}
+
+impl WalletSyncer {
+    pub async fn sync(&self) -> Result<(), FaucetError> {
+        self.client.lock().await.sync().await.map_err(|e| {
+            FaucetError::Wallet(format!("Sync failed: {}", e))
+        })?;
+        Ok(())
+    }
+}
+
 impl WalletManager {
     pub async fn new(
         data_dir: PathBuf,
         server_uri: String,
+        activation_heights: &[(String, u32)],
     ) -> Result<Self, FaucetError> {
         info!("Initializing ZingoLib LightClient");
         
@@ -57,7 +82,7 @@ impl WalletManager {
         })?;
 
         // Build configuration for regtest
-        let config = ZingoConfig::build(zingolib::config::ChainType::Regtest(Default::default()))
+        let config = ZingoConfig::build(zingolib::config::ChainType::Regtest(regtest_network(activation_heights)))
             .set_lightwalletd_uri(uri)  // Pass Uri directly, not wrapped in Arc<RwLock>
             .set_wallet_dir(data_dir.clone())
             .create();
@@ -92,13 +117,17 @@ impl WalletManager {
 
         info!("Wallet initialized successfully");
 
-        Ok(Self { client: client_mut, history })
+        Ok(Self {
+            wallet: client_mut.wallet.clone(),
+            client: Arc::new(Mutex::new(client_mut)),
+            history,
+        })
     }
 
     pub async fn get_unified_address(&self) -> Result<String, FaucetError> {
         // TODO: Update this to match actual zingolib API
         // The method names and return types need to be verified against your zingolib version
-        let _wallet = self.client.wallet.read().await;
+        let _wallet = self.wallet.read().await;
         
         // This is a placeholder - you need to check the actual API
         // Possible methods: wallet.addresses(), wallet.get_all_addresses(), etc.
@@ -109,7 +138,7 @@ impl WalletManager {
 
     pub async fn get_balance(&self) -> Result<Balance, FaucetError> {
         // TODO: Update this to match actual zingolib API
-        let _wallet = self.client.wallet.read().await;
+        let _wallet = self.wallet.read().await;
         
         // This is a placeholder - you need to check the actual API
         // The balance calculation method will depend on your zingolib version
@@ -147,21 +176,122 @@ impl WalletManager {
         ))
     }
 
-    pub async fn sync(&mut self) -> Result<(), FaucetError> {
-        self.client.sync().await.map_err(|e| {
-            FaucetError::Wallet(format!("Sync failed: {}", e))
-        })?;
-        Ok(())
+    pub fn syncer(&self) -> WalletSyncer {
+        WalletSyncer { client: self.client.clone() }
+    }
+
+    /// Highest block the wallet has fully scanned, if it has scanned any.
+    pub async fn get_wallet_height(&self) -> Option<u32> {
+        let wallet = self.wallet.read().await;
+        wallet.sync_state.fully_scanned_height().map(u32::from)
     }
 
     pub fn get_transaction_history(&self, limit: usize) -> Vec<TransactionRecord> {
         self.history.get_recent(limit)
     }
 
+    /// Notes received by the faucet wallet, newest first.
+    /// Change outputs of our own payouts are excluded so that only genuinely
+    /// incoming funds (coinbase, shielding, returned test funds) are listed.
+    pub async fn get_incoming_notes(&self) -> Result<Vec<IncomingNote>, FaucetError> {
+        let payout_txids: HashSet<&str> = self
+            .history
+            .get_all()
+            .iter()
+            .map(|tx| tx.txid.as_str())
+            .collect();
+
+        let wallet = self.wallet.read().await;
+        let mut notes = Vec::new();
+
+        for tx in wallet.wallet_transactions.values() {
+            let txid = tx.txid().to_string();
+            if payout_txids.contains(txid.as_str()) {
+                continue;
+            }
+
+            let height = tx.status().get_confirmed_height().map(u32::from);
+            let is_coinbase = tx
+                .transaction()
+                .transparent_bundle()
+                .map_or(false, |bundle| bundle.is_coinbase());
+
+            for coin in tx.transparent_coins() {
+                notes.push(IncomingNote::new(
+                    txid.clone(),
+                    Pool::Transparent,
+                    coin.value(),
+                    height,
+                    None,
+                    is_coinbase,
+                ));
+            }
+            for note in tx.sapling_notes() {
+                notes.push(IncomingNote::new(
+                    txid.clone(),
+                    Pool::Sapling,
+                    note.value(),
+                    height,
+                    memo_text(note.memo()),
+                    is_coinbase,
+                ));
+            }
+            for note in tx.orchard_notes() {
+                notes.push(IncomingNote::new(
+                    txid.clone(),
+                    Pool::Orchard,
+                    note.value(),
+                    height,
+                    memo_text(note.memo()),
+                    is_coinbase,
+                ));
+            }
+        }
+
+        // Unconfirmed notes sort first, then by descending height
+        notes.sort_by_key(|note| std::cmp::Reverse(note.height.unwrap_or(u32::MAX)));
+
+        Ok(notes)
+    }
+
     pub fn get_stats(&self) -> (usize, f64) {
         let txs = self.history.get_all();
         let count = txs.len();
         let total_sent: f64 = txs.iter().map(|tx| tx.amount).sum();
         (count, total_sent)
     }
-}
\ No newline at end of file
+}
+
+fn memo_text(memo: &Memo) -> Option<String> {
+    match memo {
+        Memo::Text(text) => Some(text.to_string()),
+        _ => None,
+    }
+}
+
+/// Regtest network with the configured activation heights, or zingolib's
+/// default of every upgrade at height 1 when none are configured.
+/// `Config::load` ensures configured heights cover every upgrade.
+fn regtest_network(activation_heights: &[(String, u32)]) -> RegtestNetwork {
+    if activation_heights.is_empty() {
+        return RegtestNetwork::default();
+    }
+
+    let height = |name: &str| {
+        activation_heights
+            .iter()
+            .find(|(upgrade, _)| upgrade == name)
+            .map(|(_, height)| u64::from(*height))
+            .expect("ACTIVATION_HEIGHTS sets every upgrade")
+    };
+
+    RegtestNetwork::new(
+        height("Overwinter"),
+        height("Sapling"),
+        height("Blossom"),
+        height("Heartwood"),
+        height("Canopy"),
+        height("NU5"),
+        height("NU6"),
+    )
+}
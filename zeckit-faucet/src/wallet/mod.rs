@@ -1,5 +1,7 @@
 pub mod manager;
 pub mod history;
+pub mod incoming;
 
 pub use manager::WalletManager;
 pub use history::{TransactionRecord, TransactionHistory};
+pub use incoming::{IncomingFilter, IncomingNote, Pool};
@@ -0,0 +1,120 @@
+use crate::error::FaucetError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Only the most recent deliveries are kept on disk.
+const MAX_RECORDS: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryRecord {
+    pub timestamp: DateTime<Utc>,
+    pub event_id: u64,
+    pub event: String,
+    pub url: String,
+    pub attempts: u32,
+    pub success: bool,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+}
+
+pub struct DeliveryLog {
+    file_path: PathBuf,
+    deliveries: Vec<DeliveryRecord>,
+}
+
+impl DeliveryLog {
+    pub fn load(data_dir: &Path) -> Result<Self, FaucetError> {
+        let file_path = data_dir.join("webhook-deliveries.json");
+
+        let deliveries = if file_path.exists() {
+            let content = fs::read_to_string(&file_path)
+                .map_err(|e| FaucetError::Internal(format!("Failed to read delivery log: {}", e)))?;
+
+            serde_json::from_str(&content)
+                .map_err(|e| FaucetError::Internal(format!("Failed to parse delivery log: {}", e)))?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            file_path,
+            deliveries,
+        })
+    }
+
+    pub fn add_delivery(&mut self, record: DeliveryRecord) -> Result<(), FaucetError> {
+        self.deliveries.push(record);
+        if self.deliveries.len() > MAX_RECORDS {
+            let excess = self.deliveries.len() - MAX_RECORDS;
+            self.deliveries.drain(..excess);
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), FaucetError> {
+        let json = serde_json::to_string_pretty(&self.deliveries)
+            .map_err(|e| FaucetError::Internal(format!("Failed to serialize delivery log: {}", e)))?;
+
+        fs::write(&self.file_path, json)
+            .map_err(|e| FaucetError::Internal(format!("Failed to write delivery log: {}", e)))?;
+
+        Ok(())
+    }
+
+    pub fn get_recent(&self, limit: usize) -> Vec<DeliveryRecord> {
+        self.deliveries
+            .iter()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn record(event_id: u64) -> DeliveryRecord {
+        DeliveryRecord {
+            timestamp: Utc::now(),
+            event_id,
+            event: "payout.success".to_string(),
+            url: "http://localhost/hook".to_string(),
+            attempts: 1,
+            success: true,
+            status_code: Some(200),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_recent_deliveries_come_newest_first() {
+        let temp_dir = tempdir().unwrap();
+        let mut log = DeliveryLog::load(temp_dir.path()).unwrap();
+        for id in 0..3 {
+            log.add_delivery(record(id)).unwrap();
+        }
+
+        let recent: Vec<u64> = log.get_recent(2).iter().map(|r| r.event_id).collect();
+        assert_eq!(recent, vec![2, 1]);
+    }
+
+    #[test]
+    fn test_log_persists_and_keeps_only_the_newest_records() {
+        let temp_dir = tempdir().unwrap();
+        let mut log = DeliveryLog::load(temp_dir.path()).unwrap();
+        for id in 0..(MAX_RECORDS as u64 + 5) {
+            log.deliveries.push(record(id));
+        }
+        log.add_delivery(record(MAX_RECORDS as u64 + 5)).unwrap();
+
+        let reloaded = DeliveryLog::load(temp_dir.path()).unwrap();
+        assert_eq!(reloaded.deliveries.len(), MAX_RECORDS);
+        assert_eq!(reloaded.deliveries[0].event_id, 6);
+        assert_eq!(reloaded.get_recent(1)[0].event_id, MAX_RECORDS as u64 + 5);
+    }
+}
@@ -0,0 +1,376 @@
+use crate::config::Config;
+use crate::events::{EventEnvelope, FaucetEvent};
+use crate::webhooks::delivery_log::{DeliveryLog, DeliveryRecord};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use sha2::Sha256;
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+use tokio::time::{sleep, Duration};
+use tracing::{info, warn};
+
+pub const SIGNATURE_HEADER: &str = "X-ZecKit-Signature";
+pub const EVENT_HEADER: &str = "X-ZecKit-Event";
+pub const DELIVERY_HEADER: &str = "X-ZecKit-Delivery";
+
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    event: &'static str,
+    id: u64,
+    timestamp: DateTime<Utc>,
+    data: &'a FaucetEvent,
+}
+
+/// Webhook event name for faucet events that are delivered to webhooks.
+/// Everything else stays on the `/events` stream only. A payout only
+/// succeeds once it is mined, so submission alone is not reported.
+pub fn webhook_event_name(event: &FaucetEvent) -> Option<&'static str> {
+    match event {
+        FaucetEvent::PayoutConfirmed { .. } => Some("payout.success"),
+        FaucetEvent::PayoutFailed { .. } => Some("payout.failure"),
+        FaucetEvent::LowBalance { .. } => Some("balance.low"),
+        FaucetEvent::SyncError { .. } => Some("sync.error"),
+        _ => None,
+    }
+}
+
+/// Server errors and rate limiting may clear up; other failures won't.
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Hex-encoded HMAC-SHA256 of `body` keyed with `secret`.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Posts signed JSON payloads to every configured webhook URL,
+/// retrying failed deliveries with exponential backoff.
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    client: Client,
+    urls: Vec<String>,
+    secret: Option<String>,
+    max_attempts: u32,
+    initial_backoff: Duration,
+    log: Arc<RwLock<DeliveryLog>>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(
+        urls: Vec<String>,
+        secret: Option<String>,
+        max_attempts: u32,
+        initial_backoff: Duration,
+        log: Arc<RwLock<DeliveryLog>>,
+    ) -> Self {
+        Self {
+            client: Client::new(),
+            urls,
+            secret,
+            max_attempts: max_attempts.max(1),
+            initial_backoff,
+            log,
+        }
+    }
+
+    pub fn from_config(config: &Config, log: Arc<RwLock<DeliveryLog>>) -> Self {
+        Self::new(
+            config.webhook_urls.clone(),
+            config.webhook_secret.clone(),
+            config.webhook_max_attempts,
+            Duration::from_secs(1),
+            log,
+        )
+    }
+
+    /// Forward events from the bus until it is closed.
+    pub async fn run(self, mut events: broadcast::Receiver<EventEnvelope>) {
+        if self.secret.is_none() {
+            warn!("WEBHOOK_SECRET is not set, webhook payloads will be unsigned");
+        }
+        info!("Delivering webhooks to {} URL(s)", self.urls.len());
+
+        loop {
+            match events.recv().await {
+                Ok(envelope) => {
+                    if webhook_event_name(&envelope.event).is_none() {
+                        continue;
+                    }
+                    for url in &self.urls {
+                        let dispatcher = self.clone();
+                        let url = url.clone();
+                        let envelope = envelope.clone();
+                        tokio::spawn(async move {
+                            dispatcher.deliver(&url, &envelope).await;
+                        });
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Webhook dispatcher missed {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+
+    /// Deliver one event to one URL, recording the outcome in the delivery log.
+    pub async fn deliver(&self, url: &str, envelope: &EventEnvelope) -> DeliveryRecord {
+        let event = webhook_event_name(&envelope.event).unwrap_or_else(|| envelope.event.name());
+        let body = serde_json::to_vec(&WebhookPayload {
+            event,
+            id: envelope.id,
+            timestamp: envelope.timestamp,
+            data: &envelope.event,
+        })
+        .expect("webhook payload is always serializable");
+
+        let mut backoff = self.initial_backoff;
+        let mut attempts = 0;
+        let mut status_code = None;
+        let mut error = None;
+        let mut success = false;
+
+        while attempts < self.max_attempts {
+            attempts += 1;
+
+            let mut request = self
+                .client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .header(EVENT_HEADER, event)
+                .header(DELIVERY_HEADER, envelope.id.to_string())
+                .timeout(Duration::from_secs(10))
+                .body(body.clone());
+            if let Some(secret) = &self.secret {
+                request = request.header(SIGNATURE_HEADER, format!("sha256={}", sign(secret, &body)));
+            }
+
+            match request.send().await {
+                Ok(resp) if resp.status().is_success() => {
+                    status_code = Some(resp.status().as_u16());
+                    error = None;
+                    success = true;
+                    break;
+                }
+                Ok(resp) => {
+                    status_code = Some(resp.status().as_u16());
+                    error = Some(format!("Webhook returned {}", resp.status()));
+
+                    // A receiver that rejects the request won't accept a retry either
+                    if !is_retryable(resp.status()) {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    status_code = None;
+                    error = Some(e.to_string());
+                }
+            }
+
+            if attempts < self.max_attempts {
+                sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+
+        if success {
+            info!("Delivered {} webhook to {} (attempt {})", event, url, attempts);
+        } else {
+            warn!(
+                "Giving up on {} webhook to {} after {} attempts: {}",
+                event,
+                url,
+                attempts,
+                error.as_deref().unwrap_or("unknown error")
+            );
+        }
+
+        let record = DeliveryRecord {
+            timestamp: Utc::now(),
+            event_id: envelope.id,
+            event: event.to_string(),
+            url: url.to_string(),
+            attempts,
+            success,
+            status_code,
+            error,
+        };
+
+        if let Err(e) = self.log.write().await.add_delivery(record.clone()) {
+            warn!("Could not record webhook delivery: {}", e);
+        }
+
+        record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn payout_event() -> EventEnvelope {
+        EventEnvelope {
+            id: 42,
+            timestamp: Utc::now(),
+            event: FaucetEvent::PayoutConfirmed {
+                txid: "abc123".to_string(),
+                address: "uregtest1test123".to_string(),
+                amount: 10.0,
+                height: 150,
+            },
+        }
+    }
+
+    fn dispatcher(url: &str, log: Arc<RwLock<DeliveryLog>>) -> WebhookDispatcher {
+        WebhookDispatcher::new(
+            vec![url.to_string()],
+            Some("secret".to_string()),
+            3,
+            Duration::from_millis(1),
+            log,
+        )
+    }
+
+    #[test]
+    fn test_signature_matches_known_vector() {
+        // RFC 4231 test case 2
+        let signature = sign("Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            signature,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_webhook_event_names() {
+        assert_eq!(webhook_event_name(&payout_event().event), Some("payout.success"));
+        let submitted = FaucetEvent::PayoutSubmitted {
+            txid: "abc123".to_string(),
+            address: "uregtest1test123".to_string(),
+            amount: 10.0,
+            memo: None,
+        };
+        assert_eq!(webhook_event_name(&submitted), None);
+        assert_eq!(webhook_event_name(&FaucetEvent::NewBlock { height: 1 }), None);
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retryable(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable(StatusCode::NOT_FOUND));
+        assert!(!is_retryable(StatusCode::GONE));
+    }
+
+    #[tokio::test]
+    async fn test_delivery_is_signed() {
+        let temp_dir = tempdir().unwrap();
+        let log = Arc::new(RwLock::new(DeliveryLog::load(temp_dir.path()).unwrap()));
+        let mut server = mockito::Server::new_async().await;
+        let envelope = payout_event();
+
+        let body = serde_json::to_vec(&WebhookPayload {
+            event: "payout.success",
+            id: envelope.id,
+            timestamp: envelope.timestamp,
+            data: &envelope.event,
+        })
+        .unwrap();
+
+        let mock = server
+            .mock("POST", "/hook")
+            .match_header("x-zeckit-event", "payout.success")
+            .match_header("x-zeckit-delivery", "42")
+            .match_header(
+                "x-zeckit-signature",
+                format!("sha256={}", sign("secret", &body)).as_str(),
+            )
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let url = format!("{}/hook", server.url());
+        let record = dispatcher(&url, log.clone()).deliver(&url, &envelope).await;
+
+        mock.assert_async().await;
+        assert!(record.success);
+        assert_eq!(record.attempts, 1);
+        assert_eq!(record.status_code, Some(200));
+        assert_eq!(log.read().await.get_recent(10).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_server_error_is_retried_and_logged() {
+        let temp_dir = tempdir().unwrap();
+        let log = Arc::new(RwLock::new(DeliveryLog::load(temp_dir.path()).unwrap()));
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("POST", "/hook")
+            .with_status(500)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let url = format!("{}/hook", server.url());
+        let record = dispatcher(&url, log).deliver(&url, &payout_event()).await;
+
+        mock.assert_async().await;
+        assert!(!record.success);
+        assert_eq!(record.attempts, 3);
+        assert_eq!(record.status_code, Some(500));
+
+        // The log survives a reload from disk
+        let reloaded = DeliveryLog::load(temp_dir.path()).unwrap();
+        assert_eq!(reloaded.get_recent(10)[0].event, "payout.success");
+    }
+
+    #[tokio::test]
+    async fn test_client_error_is_not_retried() {
+        let temp_dir = tempdir().unwrap();
+        let log = Arc::new(RwLock::new(DeliveryLog::load(temp_dir.path()).unwrap()));
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("POST", "/hook")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let url = format!("{}/hook", server.url());
+        let record = dispatcher(&url, log).deliver(&url, &payout_event()).await;
+
+        mock.assert_async().await;
+        assert!(!record.success);
+        assert_eq!(record.attempts, 1);
+        assert_eq!(record.status_code, Some(404));
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_receiver_is_retried() {
+        let temp_dir = tempdir().unwrap();
+        let log = Arc::new(RwLock::new(DeliveryLog::load(temp_dir.path()).unwrap()));
+
+        // Nothing listens on port 9 (discard) on a test machine
+        let url = "http://127.0.0.1:9/hook";
+        let record = dispatcher(url, log).deliver(url, &payout_event()).await;
+
+        assert!(!record.success);
+        assert_eq!(record.attempts, 3);
+        assert_eq!(record.status_code, None);
+        assert!(record.error.is_some());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod delivery_log;
+pub mod dispatcher;
+
+pub use delivery_log::DeliveryLog;
+pub use dispatcher::{sign, WebhookDispatcher};
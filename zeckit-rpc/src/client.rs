@@ -0,0 +1,373 @@
+use crate::error::{Result, RpcError};
+use crate::types::{
+    AddressBalance, AddressValidation, Block, BlockchainInfo, MiningInfo, RawTransaction,
+    ZAddressValidation,
+};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_MAX_RETRIES: u32 = 2;
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Serialize)]
+struct JsonRpcRequest<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: &'a [Value],
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse {
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<JsonRpcErrorObject>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcErrorObject {
+    #[serde(default)]
+    code: i64,
+    message: String,
+}
+
+/// Client for Zebra's JSON-RPC endpoint.
+///
+/// Every call has a timeout, and calls that fail for transient reasons
+/// (connection refused, timeouts, HTTP 5xx) are retried with exponential
+/// backoff. JSON-RPC errors returned by Zebra are surfaced immediately.
+#[derive(Debug, Clone)]
+pub struct ZebraRpcClient {
+    client: Client,
+    url: String,
+    timeout: Duration,
+    max_retries: u32,
+    retry_delay: Duration,
+    next_id: Arc<AtomicU64>,
+}
+
+impl ZebraRpcClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.into(),
+            timeout: DEFAULT_TIMEOUT,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            next_id: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    /// Per-request timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Number of retries after the first attempt, and the initial delay
+    /// between them (doubled after each retry).
+    pub fn with_retries(mut self, max_retries: u32, retry_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Call `method` and deserialize its result, retrying transient failures.
+    pub async fn call<T: DeserializeOwned>(&self, method: &str, params: &[Value]) -> Result<T> {
+        let mut delay = self.retry_delay;
+        let mut attempt = 0;
+
+        loop {
+            match self.call_once(method, params).await {
+                Err(e) if e.is_retryable() && attempt < self.max_retries => {
+                    attempt += 1;
+                    sleep(delay).await;
+                    delay *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Call `method` exactly once, without retrying. Used for calls that
+    /// must not be repeated, such as `generate`.
+    pub async fn call_once<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &[Value],
+    ) -> Result<T> {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0",
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            method,
+            params,
+        };
+
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&request)
+            .timeout(self.timeout)
+            .send()
+            .await
+            .map_err(|e| self.transport_error(e))?;
+
+        let status = response.status();
+        let body = response.text().await.map_err(|e| self.transport_error(e))?;
+
+        // Zebra reports JSON-RPC errors with non-2xx statuses too, so try
+        // to decode the body before looking at the status code.
+        let parsed: JsonRpcResponse = match serde_json::from_str(&body) {
+            Ok(parsed) => parsed,
+            Err(_) if !status.is_success() => {
+                return Err(RpcError::Http {
+                    status: status.as_u16(),
+                    body,
+                });
+            }
+            Err(e) => {
+                return Err(RpcError::InvalidResponse {
+                    method: method.to_string(),
+                    reason: e.to_string(),
+                });
+            }
+        };
+
+        if let Some(error) = parsed.error {
+            return Err(RpcError::JsonRpc {
+                method: method.to_string(),
+                code: error.code,
+                message: error.message,
+            });
+        }
+
+        let result = parsed.result.unwrap_or(Value::Null);
+        serde_json::from_value(result).map_err(|e| RpcError::InvalidResponse {
+            method: method.to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    fn transport_error(&self, source: reqwest::Error) -> RpcError {
+        if source.is_timeout() {
+            RpcError::Timeout(self.timeout)
+        } else {
+            RpcError::Transport {
+                url: self.url.clone(),
+                source,
+            }
+        }
+    }
+
+    pub async fn get_block_count(&self) -> Result<u64> {
+        self.call("getblockcount", &[]).await
+    }
+
+    pub async fn get_blockchain_info(&self) -> Result<BlockchainInfo> {
+        self.call("getblockchaininfo", &[]).await
+    }
+
+    /// Block by hash or height, with transaction ids.
+    pub async fn get_block(&self, hash_or_height: &str) -> Result<Block> {
+        self.call("getblock", &[Value::from(hash_or_height), Value::from(1)])
+            .await
+    }
+
+    /// Raw transaction hex.
+    pub async fn get_raw_transaction(&self, txid: &str) -> Result<String> {
+        self.call("getrawtransaction", &[Value::from(txid), Value::from(0)])
+            .await
+    }
+
+    /// Raw transaction with its mined height and confirmations.
+    pub async fn get_raw_transaction_verbose(&self, txid: &str) -> Result<RawTransaction> {
+        self.call("getrawtransaction", &[Value::from(txid), Value::from(1)])
+            .await
+    }
+
+    /// Broadcast a raw transaction, returning its txid.
+    pub async fn send_raw_transaction(&self, hex: &str) -> Result<String> {
+        self.call("sendrawtransaction", &[Value::from(hex)]).await
+    }
+
+    pub async fn validate_address(&self, address: &str) -> Result<AddressValidation> {
+        self.call("validateaddress", &[Value::from(address)]).await
+    }
+
+    pub async fn z_validate_address(&self, address: &str) -> Result<ZAddressValidation> {
+        self.call("z_validateaddress", &[Value::from(address)])
+            .await
+    }
+
+    /// Confirmed balance of transparent `addresses`.
+    pub async fn get_address_balance(&self, addresses: &[&str]) -> Result<AddressBalance> {
+        self.call("getaddressbalance", &[json!({ "addresses": addresses })])
+            .await
+    }
+
+    pub async fn get_mining_info(&self) -> Result<MiningInfo> {
+        self.call("getmininginfo", &[]).await
+    }
+
+    /// Mine `num_blocks` blocks (regtest only), returning their hashes.
+    /// Never retried, since a timed-out call may still have mined blocks.
+    pub async fn generate(&self, num_blocks: u32) -> Result<Vec<String>> {
+        self.call_once("generate", &[Value::from(num_blocks)]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+    use serde_json::json;
+
+    fn client(server: &mockito::Server) -> ZebraRpcClient {
+        ZebraRpcClient::new(server.url()).with_retries(2, Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn test_get_block_count() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(json!({ "method": "getblockcount" })))
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":101}"#)
+            .create_async()
+            .await;
+
+        assert_eq!(client(&server).get_block_count().await.unwrap(), 101);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_address_balance() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(json!({
+                "method": "getaddressbalance",
+                "params": [{ "addresses": ["tmBsTi2xWTjUdEXnuTceL7fecEQKeWaPDJd"] }]
+            })))
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"balance":625000000}}"#)
+            .create_async()
+            .await;
+
+        let balance = client(&server)
+            .get_address_balance(&["tmBsTi2xWTjUdEXnuTceL7fecEQKeWaPDJd"])
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(balance.zec(), 6.25);
+    }
+
+    #[tokio::test]
+    async fn test_json_rpc_error_is_not_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/")
+            .with_status(500)
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"error":{"code":-5,"message":"No such mempool or main chain transaction"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let err = client(&server)
+            .get_raw_transaction_verbose("00")
+            .await
+            .unwrap_err();
+
+        mock.assert_async().await;
+        match err {
+            RpcError::JsonRpc { code, method, .. } => {
+                assert_eq!(code, -5);
+                assert_eq!(method, "getrawtransaction");
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_errors_are_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/")
+            .with_status(503)
+            .with_body("service unavailable")
+            .expect(3)
+            .create_async()
+            .await;
+
+        let err = client(&server).get_block_count().await.unwrap_err();
+
+        mock.assert_async().await;
+        assert!(matches!(err, RpcError::Http { status: 503, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_result_shape() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":"not a number"}"#)
+            .create_async()
+            .await;
+
+        let err = client(&server).get_block_count().await.unwrap_err();
+        assert!(matches!(err, RpcError::InvalidResponse { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_blockchain_info_activation_heights() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/")
+            .with_body(
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "chain": "regtest",
+                        "blocks": 10,
+                        "bestblockhash": "0a",
+                        "upgrades": {
+                            "c2d6d0b4": { "name": "NU5", "activationheight": 1, "status": "active" },
+                            "c8e71055": { "name": "NU6", "activationheight": 5, "status": "active" }
+                        }
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let info = client(&server).get_blockchain_info().await.unwrap();
+        assert_eq!(info.activation_height("nu5"), Some(1));
+        assert_eq!(info.activation_height("NU6"), Some(5));
+        assert_eq!(info.activation_height("NU7"), None);
+
+        assert!(info
+            .activation_height_mismatches([("NU5", 1), ("NU6", 5)])
+            .is_empty());
+        assert_eq!(
+            info.activation_height_mismatches([("NU5", 1), ("NU6", 10), ("NU7", 20)]),
+            vec![
+                "NU6: expected 10, Zebra has 5".to_string(),
+                "NU7: expected 20, not scheduled by Zebra".to_string(),
+            ]
+        );
+    }
+}
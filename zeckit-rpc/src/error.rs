@@ -0,0 +1,42 @@
+use std::time::Duration;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, RpcError>;
+
+#[derive(Error, Debug)]
+pub enum RpcError {
+    #[error("Zebra RPC unreachable at {url}: {source}")]
+    Transport {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("Zebra RPC timed out after {0:?}")]
+    Timeout(Duration),
+
+    #[error("Zebra RPC returned HTTP {status}: {body}")]
+    Http { status: u16, body: String },
+
+    #[error("{method} failed (code {code}): {message}")]
+    JsonRpc {
+        method: String,
+        code: i64,
+        message: String,
+    },
+
+    #[error("Invalid {method} response: {reason}")]
+    InvalidResponse { method: String, reason: String },
+}
+
+impl RpcError {
+    /// Whether the call may succeed if repeated (connection problems,
+    /// timeouts and server-side HTTP errors). JSON-RPC errors are final.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            RpcError::Transport { .. } | RpcError::Timeout(_) => true,
+            RpcError::Http { status, .. } => *status >= 500,
+            RpcError::JsonRpc { .. } | RpcError::InvalidResponse { .. } => false,
+        }
+    }
+}
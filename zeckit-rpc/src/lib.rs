@@ -0,0 +1,9 @@
+//! Typed client for the subset of Zebra's JSON-RPC interface used by ZecKit.
+
+pub mod client;
+pub mod error;
+pub mod types;
+
+pub use client::ZebraRpcClient;
+pub use error::{Result, RpcError};
+pub use types::*;
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Result of `getblockchaininfo`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockchainInfo {
+    pub chain: String,
+    pub blocks: u32,
+    pub bestblockhash: String,
+    #[serde(default)]
+    pub estimatedheight: Option<u32>,
+    /// Network upgrades keyed by consensus branch id.
+    #[serde(default)]
+    pub upgrades: BTreeMap<String, NetworkUpgrade>,
+}
+
+impl BlockchainInfo {
+    /// Activation height of a network upgrade by name, e.g. `"NU5"`.
+    pub fn activation_height(&self, name: &str) -> Option<u32> {
+        self.upgrades
+            .values()
+            .find(|upgrade| upgrade.name.eq_ignore_ascii_case(name))
+            .map(|upgrade| upgrade.activationheight)
+    }
+
+    /// Compare expected activation heights, keyed by upgrade name, against
+    /// the ones Zebra reports. Returns a description of each mismatch.
+    pub fn activation_height_mismatches<'a>(
+        &self,
+        expected: impl IntoIterator<Item = (&'a str, u32)>,
+    ) -> Vec<String> {
+        expected
+            .into_iter()
+            .filter_map(|(name, height)| match self.activation_height(name) {
+                Some(actual) if actual == height => None,
+                Some(actual) => Some(format!("{}: expected {}, Zebra has {}", name, height, actual)),
+                None => Some(format!("{}: expected {}, not scheduled by Zebra", name, height)),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkUpgrade {
+    pub name: String,
+    pub activationheight: u32,
+    pub status: String,
+}
+
+/// Result of `getblock` with verbosity 1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Block {
+    pub hash: String,
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub confirmations: Option<i64>,
+    #[serde(default)]
+    pub time: Option<i64>,
+    #[serde(default)]
+    pub tx: Vec<String>,
+}
+
+/// Result of `getrawtransaction` with verbose output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawTransaction {
+    pub hex: String,
+    /// Missing (or negative) while the transaction is in the mempool.
+    #[serde(default)]
+    pub height: Option<i64>,
+    #[serde(default)]
+    pub confirmations: Option<i64>,
+    #[serde(default)]
+    pub blockhash: Option<String>,
+}
+
+impl RawTransaction {
+    /// Height of the block containing this transaction, if it has been mined.
+    pub fn mined_height(&self) -> Option<u32> {
+        self.height
+            .filter(|height| *height >= 0)
+            .and_then(|height| u32::try_from(height).ok())
+    }
+}
+
+/// Result of `validateaddress` (transparent addresses).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressValidation {
+    pub isvalid: bool,
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub isscript: Option<bool>,
+}
+
+/// Result of `getaddressbalance`: the confirmed transparent balance of the
+/// given addresses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressBalance {
+    /// Balance in zatoshis.
+    pub balance: u64,
+}
+
+impl AddressBalance {
+    pub fn zec(&self) -> f64 {
+        self.balance as f64 / 100_000_000.0
+    }
+}
+
+/// Result of `z_validateaddress` (transparent, Sapling and unified addresses).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZAddressValidation {
+    pub isvalid: bool,
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub address_type: Option<String>,
+}
+
+/// Result of `getmininginfo`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MiningInfo {
+    pub blocks: u32,
+    pub chain: String,
+    #[serde(default)]
+    pub networksolps: Option<u64>,
+    #[serde(default)]
+    pub networkhashps: Option<u64>,
+    #[serde(default)]
+    pub testnet: Option<bool>,
+}